@@ -0,0 +1,115 @@
+//! A01:2021 dataflow analysis for Supabase mutations. Rather than asking
+//! "does this file contain the words `getUser` and `user_id` anywhere"
+//! (easy to satisfy by accident, and blind to *which* mutation they guard),
+//! this walks each `.insert(`/`.update(`/`.delete(`/`.upsert(` call's
+//! enclosing function body backward to confirm an identity fetch precedes
+//! it, then inspects that specific call's chain/arguments for an ownership
+//! constraint binding the mutation to the fetched identity.
+
+use crate::ast_query::ParsedFile;
+use once_cell::sync::Lazy;
+use regex::Regex;
+use tree_sitter::Node;
+
+const MUTATION_METHODS: [&str; 4] = ["insert", "update", "delete", "upsert"];
+
+static IDENTITY_RE: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r"\.auth\.(getUser|getSession)\s*\(").unwrap());
+
+/// `.eq('user_id', user.id)` / `.eq("owner_id", session?.user.id)`-style
+/// ownership filters, as chained onto an `update`/`delete`/`upsert` call.
+static OWNERSHIP_FILTER_RE: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r#"\.eq\(\s*['"](user_id|owner_id|owner|created_by)['"]\s*,\s*\w+[?.]*\.(id|user\.id)"#).unwrap()
+});
+
+/// `{ user_id: user.id, ... }`-style owner-column binding in an `insert`'s
+/// own row argument.
+static OWNER_BINDING_RE: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r"(user_id|owner_id|owner|created_by)\s*:\s*\w+[?.]*\.(id|user\.id)").unwrap()
+});
+
+/// One mutation call site missing a required access-control guard.
+pub struct MutationGap {
+    pub line: u32,
+    pub method: &'static str,
+    pub call_site: String,
+    /// No `supabase.auth.getUser()`/`getSession()` precedes the call at all.
+    pub missing_identity: bool,
+    /// Identity was fetched, but the mutation isn't constrained to it.
+    pub missing_ownership: bool,
+}
+
+/// Finds every Supabase mutation call in `parsed` missing a guard. A call
+/// with both an identity fetch and an ownership constraint isn't returned.
+pub fn analyze(parsed: &ParsedFile) -> Vec<MutationGap> {
+    parsed
+        .walk()
+        .filter(|n| n.kind() == "call_expression")
+        .filter_map(|call| mutation_gap(parsed, call))
+        .collect()
+}
+
+fn mutation_gap(parsed: &ParsedFile, call: Node) -> Option<MutationGap> {
+    let callee = call.child(0)?;
+    if callee.kind() != "member_expression" {
+        return None;
+    }
+    let property = callee.child_by_field_name("property")?;
+    let method = MUTATION_METHODS.iter().find(|m| **m == parsed.text(property))?;
+
+    let handler = enclosing_function(call).unwrap_or_else(|| parsed.root());
+    let preceding = &parsed.text(handler)[..(call.start_byte() - handler.start_byte()).min(parsed.text(handler).len())];
+    let missing_identity = !IDENTITY_RE.is_match(preceding);
+
+    let missing_ownership = if missing_identity {
+        false // already flagged as a missing-authentication gap; don't double-report
+    } else if *method == "insert" || *method == "upsert" {
+        let args_text = call.child_by_field_name("arguments").map(|a| parsed.text(a)).unwrap_or("");
+        !OWNER_BINDING_RE.is_match(args_text)
+    } else {
+        let chain_text = parsed.text(chain_root(call));
+        !OWNERSHIP_FILTER_RE.is_match(chain_text)
+    };
+
+    if !missing_identity && !missing_ownership {
+        return None;
+    }
+
+    Some(MutationGap {
+        line: call.start_position().row as u32 + 1,
+        method,
+        call_site: parsed.text(chain_root(call)).lines().next().unwrap_or("").trim().to_string(),
+        missing_identity,
+        missing_ownership,
+    })
+}
+
+/// Walks up from `node` to the nearest enclosing function-like node, so the
+/// backward identity check only looks within the current handler rather
+/// than the whole file.
+fn enclosing_function(node: Node) -> Option<Node> {
+    let mut current = node.parent();
+    while let Some(n) = current {
+        if matches!(n.kind(), "function_declaration" | "function_expression" | "arrow_function" | "method_definition") {
+            return Some(n);
+        }
+        current = n.parent();
+    }
+    None
+}
+
+/// Walks up through the member/call chain a mutation call is part of (e.g.
+/// `supabase.from('items').update({...}).eq('user_id', user.id)`) to the
+/// outermost call, so a chained `.eq()` filter applied *after* the mutation
+/// is still visible to the ownership check.
+fn chain_root(node: Node) -> Node {
+    let mut current = node;
+    while let Some(parent) = current.parent() {
+        if parent.kind() == "call_expression" || parent.kind() == "member_expression" {
+            current = parent;
+        } else {
+            break;
+        }
+    }
+    current
+}
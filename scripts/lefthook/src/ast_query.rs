@@ -0,0 +1,112 @@
+//! Shared tree-sitter parsing core for TypeScript/JSX. Plain
+//! `content.contains("...")`/regex scanning produces false positives inside
+//! comments, string literals, and unrelated identifiers - `useEffect` +
+//! `fetch(` anywhere in the file, or `target="_blank"` sitting in a doc
+//! comment. This parses a file once into a real AST so a check can query
+//! genuine call expressions, JSX attributes, and directive prologues
+//! instead. Callers fall back to the existing regex path when [`parse`]
+//! returns `None` (grammar/parser failure - tree-sitter recovers from plain
+//! syntax errors in the source and still returns a partial tree for those).
+
+use tree_sitter::{Node, Parser, Tree};
+
+pub struct ParsedFile {
+    tree: Tree,
+    source: String,
+}
+
+/// Parses `source` as TSX, a strict superset of the `.ts`/`.jsx`/`.js`
+/// syntax the checks in this chunk care about, so one grammar covers all of
+/// them.
+pub fn parse(source: &str) -> Option<ParsedFile> {
+    let mut parser = Parser::new();
+    parser.set_language(&tree_sitter_typescript::LANGUAGE_TSX.into()).ok()?;
+    let tree = parser.parse(source, None)?;
+    Some(ParsedFile { tree, source: source.to_string() })
+}
+
+impl ParsedFile {
+    /// The root `program` node, for callers that need to walk/query the tree
+    /// themselves rather than through one of the methods below.
+    pub fn root(&self) -> Node<'_> {
+        self.tree.root_node()
+    }
+
+    pub fn text(&self, node: Node) -> &str {
+        node.utf8_text(self.source.as_bytes()).unwrap_or("")
+    }
+
+    /// Depth-first iterator over every node in the tree.
+    pub fn walk(&self) -> impl Iterator<Item = Node<'_>> {
+        let mut stack = vec![self.tree.root_node()];
+        std::iter::from_fn(move || {
+            let node = stack.pop()?;
+            let mut cursor = node.walk();
+            stack.extend(node.children(&mut cursor));
+            Some(node)
+        })
+    }
+
+    /// Whether `directive` (e.g. `"use client"`) appears as a genuine
+    /// *directive prologue* entry - a leading string-literal expression
+    /// statement in the `program` node - rather than merely as a substring
+    /// anywhere in the file (a later comment or unrelated string literal
+    /// won't match).
+    pub fn has_top_of_file_directive(&self, directive: &str) -> bool {
+        let root = self.tree.root_node();
+        let mut cursor = root.walk();
+        for stmt in root.children(&mut cursor) {
+            if stmt.kind() != "expression_statement" {
+                break;
+            }
+            let Some(expr) = stmt.child(0) else { break };
+            if expr.kind() != "string" {
+                break;
+            }
+            if self.text(expr).trim_matches(|c| c == '"' || c == '\'') == directive {
+                return true;
+            }
+        }
+        false
+    }
+
+    /// 1-indexed line numbers of genuine call expressions to any of
+    /// `names` - an actual `useEffect(...)` call, not the substring
+    /// "useEffect" inside a comment or a differently-named identifier.
+    pub fn call_expression_lines(&self, names: &[&str]) -> Vec<u32> {
+        self.walk()
+            .filter(|n| n.kind() == "call_expression")
+            .filter_map(|n| {
+                let callee = n.child(0)?;
+                names.contains(&self.text(callee)).then(|| n.start_position().row as u32 + 1)
+            })
+            .collect()
+    }
+
+    /// 1-indexed line numbers of JSX elements with `target="_blank"` whose
+    /// `rel` attribute on that *same* opening element doesn't contain
+    /// `noopener`/`noreferrer` - so a safe `rel` on an unrelated element
+    /// elsewhere in the file can't suppress a real finding.
+    pub fn unsafe_target_blank_lines(&self) -> Vec<u32> {
+        self.walk()
+            .filter(|n| n.kind() == "jsx_opening_element" || n.kind() == "jsx_self_closing_element")
+            .filter_map(|element| {
+                let mut cursor = element.walk();
+                let attrs: Vec<Node> = element.children(&mut cursor).filter(|c| c.kind() == "jsx_attribute").collect();
+
+                let has_blank_target =
+                    attrs.iter().any(|a| self.text(*a).starts_with("target") && self.text(*a).contains("_blank"));
+                if !has_blank_target {
+                    return None;
+                }
+
+                let rel_is_safe = attrs.iter().any(|a| {
+                    let text = self.text(*a);
+                    text.starts_with("rel") && (text.contains("noopener") || text.contains("noreferrer"))
+                });
+
+                (!rel_is_safe).then(|| element.start_position().row as u32 + 1)
+            })
+            .collect()
+    }
+}
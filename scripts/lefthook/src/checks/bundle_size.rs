@@ -1,3 +1,5 @@
+use crate::config::{CheckGlobs, FoodcheckConfig, GlobMatcher};
+use crate::report::{self, Finding, Severity};
 use crate::utils::{format_bytes, print_header, print_info, print_success, print_warning};
 use anyhow::Result;
 use std::fs;
@@ -28,20 +30,19 @@ pub fn run() -> Result<()> {
     let mut total_size: u64 = 0;
     let mut js_files: Vec<(String, u64)> = Vec::new();
 
-    // Walk through the build directory
-    for entry in WalkDir::new(&static_dir)
-        .into_iter()
-        .filter_map(|e| e.ok())
-    {
-        let path = entry.path();
-        if path.is_file() {
-            if let Ok(metadata) = fs::metadata(path) {
-                let size = metadata.len();
-                total_size += size;
-
-                if path.extension().map_or(false, |ext| ext == "js") {
-                    js_files.push((path.display().to_string(), size));
-                }
+    // A configured [bundle_size] section lets a project scope the scan (or
+    // add ignore globs for vendored chunks); fall back to walking the whole
+    // static dir when none is set.
+    let config = FoodcheckConfig::load(Path::new(".")).unwrap_or_default();
+    let globs = config.globs_for("bundle_size");
+
+    for path in bundle_files(&static_dir, &globs) {
+        if let Ok(metadata) = fs::metadata(&path) {
+            let size = metadata.len();
+            total_size += size;
+
+            if path.extension().map_or(false, |ext| ext == "js") {
+                js_files.push((path.display().to_string(), size));
             }
         }
     }
@@ -59,6 +60,15 @@ pub fn run() -> Result<()> {
         let size_kb = size / 1024;
         if size_kb > MAX_MAIN_BUNDLE_KB {
             print_warning(&format!("  {} - {} (exceeds {}KB limit)", filename, format_bytes(*size), MAX_MAIN_BUNDLE_KB));
+            report::push(Finding {
+                check: "bundle_size".to_string(),
+                file: file.clone(),
+                line: None,
+                severity: Severity::Warning,
+                rule: "bundle-too-large".to_string(),
+                message: format!("Bundle exceeds {}KB limit ({})", MAX_MAIN_BUNDLE_KB, format_bytes(*size)),
+                owasp: None,
+            });
         } else {
             print_info(&format!("  {} - {}", filename, format_bytes(*size)));
         }
@@ -73,9 +83,51 @@ pub fn run() -> Result<()> {
             MAX_TOTAL_SIZE_MB
         ));
         print_info("Consider code splitting or removing unused dependencies");
+        report::push(Finding {
+            check: "bundle_size".to_string(),
+            file: static_dir.display().to_string(),
+            line: None,
+            severity: Severity::Warning,
+            rule: "total-bundle-too-large".to_string(),
+            message: format!("Total build size exceeds {}MB limit ({})", MAX_TOTAL_SIZE_MB, format_bytes(total_size)),
+            owasp: None,
+        });
     } else {
         print_success(&format!("Total build size: {}", format_bytes(total_size)));
     }
 
     Ok(())
 }
+
+/// Resolves the set of files to size up: the configured include/ignore
+/// globs when `[bundle_size]` is set in `.foodcheck.toml`, otherwise a
+/// single-pass walk of `static_dir` that honors `.gitignore` (pass
+/// `--no-ignore` to audit ignored scratch/vendored output too).
+fn bundle_files(static_dir: &Path, globs: &CheckGlobs) -> Vec<std::path::PathBuf> {
+    if !globs.include.is_empty() || !globs.ignore.is_empty() {
+        let mut scoped = globs.clone();
+        if scoped.include.is_empty() {
+            // Ignore globs alone still scope the default static-dir walk.
+            scoped.include.push(format!("{}/**/*", static_dir.display()));
+        }
+        return GlobMatcher::build(Path::new("."), &scoped)
+            .map(|m| m.collect_files())
+            .unwrap_or_default();
+    }
+
+    if crate::utils::no_ignore() {
+        WalkDir::new(static_dir)
+            .into_iter()
+            .filter_map(|e| e.ok())
+            .filter(|e| e.path().is_file())
+            .map(|e| e.path().to_path_buf())
+            .collect()
+    } else {
+        ignore::WalkBuilder::new(static_dir)
+            .build()
+            .filter_map(|e| e.ok())
+            .filter(|e| e.file_type().map(|t| t.is_file()).unwrap_or(false))
+            .map(|e| e.path().to_path_buf())
+            .collect()
+    }
+}
@@ -1,9 +1,17 @@
-use crate::utils::{print_error, print_info, print_success};
+use crate::repo_config::ConventionalCommitConfig;
+use crate::utils::{print_error, print_info, print_success, print_warning};
 use anyhow::Result;
 use regex::Regex;
 use std::fs;
 
-pub fn run(message_file: &str) -> Result<()> {
+/// Matches a footer line's leading `<token>: ` (or the two-word `BREAKING
+/// CHANGE: ` token the spec special-cases), per the Conventional Commits
+/// footer grammar.
+fn footer_token_pattern() -> Regex {
+    Regex::new(r"^(BREAKING CHANGE|[A-Za-z][A-Za-z-]*): ").unwrap()
+}
+
+pub fn run(message_file: &str, config: &ConventionalCommitConfig) -> Result<()> {
     let commit_msg = fs::read_to_string(message_file)?;
     let commit_msg = commit_msg.trim();
 
@@ -19,20 +27,175 @@ pub fn run(message_file: &str) -> Result<()> {
         return Ok(());
     }
 
-    // Validate conventional commit format
-    let pattern = Regex::new(
-        r"^(feat|fix|docs|style|refactor|test|chore|perf|ci|build|revert)(\(.+\))?: .{1,72}",
-    )
+    let message = split_message(commit_msg);
+
+    // Validate the header against the configured types and subject length,
+    // falling back to today's hardcoded defaults when `.lefthook-rs.toml`
+    // doesn't set them. A `!` right before the colon (`feat!:` or
+    // `feat(scope)!:`) marks a breaking change, same as the spec.
+    let types = config.allowed_types.join("|");
+    let header_pattern = Regex::new(&format!(
+        r"^({})(\(([^)]+)\))?(!)?: (.{{1,{}}})$",
+        types, config.max_subject_len
+    ))
     .unwrap();
 
-    if pattern.is_match(commit_msg) {
-        print_success("Commit message follows Conventional Commits format");
-        return Ok(());
+    let Some(caps) = header_pattern.captures(message.header) else {
+        print_header_help(message.header);
+        return Err(anyhow::anyhow!("Invalid commit message format"));
+    };
+
+    let scope = caps.get(3).map(|m| m.as_str());
+
+    if let Some(scope) = scope {
+        if !config.allowed_scopes.is_empty() && !config.allowed_scopes.iter().any(|s| s == scope) {
+            print_error(&format!(
+                "Scope '{}' is not in the configured allowed_scopes: {}",
+                scope,
+                config.allowed_scopes.join(", ")
+            ));
+            if let Some(suggestion) = nearest_scope(scope, &config.allowed_scopes) {
+                print_info(&format!("Did you mean '{}'?", suggestion));
+            }
+            return Err(anyhow::anyhow!("Invalid commit scope"));
+        }
+    }
+
+    let footers = join_footer_continuations(&message.footers);
+    let footer_pattern = footer_token_pattern();
+    for line in &footers {
+        if !footer_pattern.is_match(line) {
+            print_error(&format!("Footer line does not match '<token>: <value>' format: {}", line));
+            return Err(anyhow::anyhow!("Malformed commit footer"));
+        }
     }
 
+    // Unlike the `!` marker, a `BREAKING CHANGE:` footer is valid on a commit
+    // of any type per the spec - it's the presence of the footer itself that
+    // signals the break, not the header, so there's nothing further to check
+    // here.
+
+    let issue_pattern = Regex::new(r"#\d+").unwrap();
+    for line in &footers {
+        if let Some(rest) = line.strip_prefix("Refs: ").or_else(|| line.strip_prefix("Closes: ")) {
+            if !issue_pattern.is_match(rest) {
+                let token = line.split(':').next().unwrap_or("Refs");
+                print_error(&format!("'{}:' footer should reference an issue (e.g. #123): {}", token, line));
+                return Err(anyhow::anyhow!("Footer does not reference an issue"));
+            }
+        }
+    }
+
+    for (i, line) in message.body.iter().enumerate() {
+        if line.chars().count() > config.body_wrap_width {
+            print_warning(&format!(
+                "Body line {} exceeds the configured {}-column wrap width",
+                i + 1,
+                config.body_wrap_width
+            ));
+        }
+    }
+
+    print_success("Commit message follows Conventional Commits format");
+    Ok(())
+}
+
+/// A commit message split into its three Conventional Commits parts: the
+/// header line, the body paragraphs, and a trailing footer paragraph (only
+/// recognized as footers when its first line already looks like one -
+/// otherwise it's just more body text).
+struct ParsedMessage<'a> {
+    header: &'a str,
+    body: Vec<&'a str>,
+    footers: Vec<&'a str>,
+}
+
+fn split_message(commit_msg: &str) -> ParsedMessage<'_> {
+    let mut lines = commit_msg.lines();
+    let header = lines.next().unwrap_or("");
+
+    let mut paragraphs: Vec<Vec<&str>> = Vec::new();
+    let mut current: Vec<&str> = Vec::new();
+    for line in lines {
+        if line.trim().is_empty() {
+            if !current.is_empty() {
+                paragraphs.push(std::mem::take(&mut current));
+            }
+        } else {
+            current.push(line);
+        }
+    }
+    if !current.is_empty() {
+        paragraphs.push(current);
+    }
+
+    let footer_pattern = footer_token_pattern();
+    let is_footer_paragraph = paragraphs.last().is_some_and(|p| p.first().is_some_and(|l| footer_pattern.is_match(l)));
+    let footers = if is_footer_paragraph { paragraphs.pop().unwrap() } else { Vec::new() };
+    let body = paragraphs.into_iter().flatten().collect();
+
+    ParsedMessage { header, body, footers }
+}
+
+/// Joins wrapped continuation lines back onto the footer they belong to.
+/// Per the Conventional Commits footer grammar, only a footer's first line
+/// carries the `<token>: ` prefix - any following line that isn't itself a
+/// new `<token>: ` is a continuation of that footer's value, not a footer of
+/// its own, so it shouldn't be validated against the token pattern.
+fn join_footer_continuations(lines: &[&str]) -> Vec<String> {
+    let footer_pattern = footer_token_pattern();
+    let mut footers: Vec<String> = Vec::new();
+
+    for &line in lines {
+        if footer_pattern.is_match(line) || footers.is_empty() {
+            footers.push(line.to_string());
+        } else {
+            let last = footers.last_mut().unwrap();
+            last.push(' ');
+            last.push_str(line.trim());
+        }
+    }
+
+    footers
+}
+
+/// Levenshtein edit distance between `a` and `b`, used to suggest the
+/// nearest configured scope when the commit's scope isn't in the list.
+fn lev_distance(a: &str, b: &str) -> usize {
+    let a: Vec<char> = a.chars().collect();
+    let b: Vec<char> = b.chars().collect();
+    let mut prev: Vec<usize> = (0..=b.len()).collect();
+
+    for i in 1..=a.len() {
+        let mut curr = vec![i; b.len() + 1];
+        for j in 1..=b.len() {
+            curr[j] = if a[i - 1] == b[j - 1] {
+                prev[j - 1]
+            } else {
+                1 + prev[j - 1].min(prev[j]).min(curr[j - 1])
+            };
+        }
+        prev = curr;
+    }
+
+    prev[b.len()]
+}
+
+/// Nearest configured scope to `scope` by edit distance, within a distance
+/// of 3 (otherwise there's nothing close enough to be worth suggesting).
+fn nearest_scope<'a>(scope: &str, allowed: &'a [String]) -> Option<&'a str> {
+    allowed
+        .iter()
+        .map(|s| (s.as_str(), lev_distance(scope, s)))
+        .min_by_key(|(_, distance)| *distance)
+        .filter(|(_, distance)| *distance <= 3)
+        .map(|(s, _)| s)
+}
+
+fn print_header_help(commit_msg_header: &str) {
     print_error("Commit message must follow Conventional Commits format");
     println!();
-    println!("Format: <type>(<scope>): <description>");
+    println!("Format: <type>(<scope>)!: <description>");
     println!();
     println!("Valid types:");
     println!("  feat     - New feature");
@@ -60,8 +223,7 @@ pub fn run(message_file: &str) -> Result<()> {
     println!("  fix(chat): resolve message duplication in realtime");
     println!("  docs: update API reference documentation");
     println!("  refactor(map): simplify marker clustering logic");
+    println!("  feat(api)!: drop the legacy v1 search endpoint");
     println!();
-    print_info(&format!("Your message: {}", commit_msg));
-
-    Err(anyhow::anyhow!("Invalid commit message format"))
+    print_info(&format!("Your message: {}", commit_msg_header));
 }
@@ -8,13 +8,25 @@
 //! - Runtime security (eval, prototype pollution)
 //! - FoodShare-specific patterns
 
+use crate::access_control;
+use crate::ast_query;
+use crate::config::FoodcheckConfig;
+use crate::jwt_inspect;
+use crate::osv_advisories;
+use crate::report::{self, Finding};
+use crate::rule_pack::{self, Rule};
+use crate::security_baseline;
+use crate::sri;
 use crate::utils::{
     filter_files_by_extension, get_staged_diff, get_staged_files, print_error, print_header,
     print_success, print_verbose, print_warning,
 };
 use anyhow::Result;
 use regex::Regex;
+use std::collections::BTreeMap;
 use std::fs;
+use std::path::Path;
+use std::process::Command;
 
 /// Security issue severity
 #[derive(Clone, Copy)]
@@ -30,9 +42,123 @@ struct SecurityIssue {
     file: String,
     message: String,
     owasp: Option<&'static str>,
+    /// New-file line number, when the finding came from a parsed diff hunk
+    /// (see `parse_diff_added_lines`) rather than a full-file scan.
+    line: Option<u32>,
+}
+
+/// One added (`+`) line from a unified diff, resolved to the real file it
+/// lands in and its line number in the *new* file.
+struct DiffAddedLine {
+    file: String,
+    line: u32,
+    content: String,
+}
+
+/// Walks a unified diff once and returns every added line with its real
+/// file path and new-file line number, replacing the naive
+/// `diff.lines().filter(|l| l.starts_with('+'))` scan (which also matches
+/// `+++ b/<path>` headers and can't report a line number).
+///
+/// Tracks the current file from `+++ b/<path>` lines (renames/deletions
+/// whose target is `/dev/null` are skipped - nothing to flag there) and a
+/// running new-file line counter from each hunk header
+/// (`@@ -old_start,old_len +new_start,new_len @@`, where `,len` is omitted
+/// when the length is 1), incremented by context and added lines but not
+/// by removed lines.
+fn parse_diff_added_lines(diff: &str) -> Vec<DiffAddedLine> {
+    let hunk_header = Regex::new(r"^@@ -\d+(?:,\d+)? \+(\d+)(?:,\d+)? @@").unwrap();
+    let mut entries = Vec::new();
+    let mut current_file: Option<String> = None;
+    let mut new_line: u32 = 0;
+
+    for line in diff.lines() {
+        if let Some(path) = line.strip_prefix("+++ ") {
+            let path = path.trim();
+            current_file = (path != "/dev/null").then(|| path.trim_start_matches("b/").to_string());
+            continue;
+        }
+        if let Some(caps) = hunk_header.captures(line) {
+            new_line = caps[1].parse().unwrap_or(1);
+            continue;
+        }
+        if line.starts_with("\\ No newline at end of file") || line.starts_with("--- ") {
+            continue;
+        }
+
+        if let Some(content) = line.strip_prefix('+') {
+            if let Some(file) = &current_file {
+                entries.push(DiffAddedLine {
+                    file: file.clone(),
+                    line: new_line,
+                    content: content.to_string(),
+                });
+            }
+            new_line += 1;
+        } else if line.starts_with(' ') {
+            new_line += 1;
+        }
+        // Removed ('-') and other metadata lines (e.g. "diff --git") don't
+        // advance the new-file counter.
+    }
+
+    entries
+}
+
+/// Per-severity caps for the CI gate: `None` means that severity never
+/// fails the run (findings still print and count), `Some(n)` fails once
+/// more than `n` findings of that severity are outstanding.
+#[derive(Clone, Copy)]
+pub struct GateThresholds {
+    pub max_critical: Option<usize>,
+    pub max_high: Option<usize>,
+    pub max_medium: Option<usize>,
+    pub max_low: Option<usize>,
+}
+
+impl Default for GateThresholds {
+    /// Matches the scanner's historical behavior: any Critical/High finding
+    /// fails the run, Medium/Low are advisory only.
+    fn default() -> Self {
+        Self {
+            max_critical: Some(0),
+            max_high: Some(0),
+            max_medium: None,
+            max_low: None,
+        }
+    }
 }
 
 pub fn run(files: &[String]) -> Result<()> {
+    run_with_thresholds(files, GateThresholds::default())
+}
+
+/// Same scan as [`run`], but gates the exit code on caller-supplied
+/// per-severity thresholds instead of the hardcoded "any Critical/High
+/// fails" rule, so CI can tune what's blocking vs. advisory.
+pub fn run_with_thresholds(files: &[String], thresholds: GateThresholds) -> Result<()> {
+    run_full(files, thresholds, None, false, false)
+}
+
+/// Same as [`run_with_thresholds`], plus an optional live scan of a deployed
+/// URL (TLS posture via `testssl.sh` and a response-header check) when
+/// `scan_url` is set, and live SRI hash computation/verification for
+/// external `<script>`/`<link>` tags when `verify_sri` is set (otherwise
+/// that check degrades to its offline "no integrity attribute" warning).
+/// The static, source-only checks always run; these are opt-in because both
+/// require network access an offline/CI run may not have.
+///
+/// `write_baseline` records the current finding set to `.securityignore.json`
+/// instead of gating on it, mirroring `test_coverage::run_per_file`'s
+/// `write_baseline` mode - for adopting the scanner on an existing codebase
+/// without a flood of pre-existing issues blocking the first commit.
+pub fn run_full(
+    files: &[String],
+    thresholds: GateThresholds,
+    scan_url: Option<&str>,
+    verify_sri: bool,
+    write_baseline: bool,
+) -> Result<()> {
     print_header("🛡️ Advanced Security Scanner (OWASP + Next.js/React/Vercel)");
 
     let files = if files.is_empty() {
@@ -75,12 +201,14 @@ pub fn run(files: &[String]) -> Result<()> {
     // =========================================================================
     print_verbose("🔍 OWASP A01: Checking access control...");
     check_access_control(&files, &mut issues);
+    check_mutation_authorization_dataflow(&files, &mut issues);
 
     // =========================================================================
     // OWASP A02:2021 - Cryptographic Failures
     // =========================================================================
     print_verbose("🔍 OWASP A02: Checking cryptographic issues...");
     check_crypto_failures(&files, &diff, &mut issues);
+    check_high_entropy_secrets(&files, &diff, &mut issues);
 
     // =========================================================================
     // OWASP A04:2021 - Insecure Design (Path Traversal, Open Redirect)
@@ -118,11 +246,17 @@ pub fn run(files: &[String]) -> Result<()> {
     print_verbose("🔍 Checking Vercel Edge/Middleware security...");
     check_vercel_security(&files, &mut issues);
 
+    // =========================================================================
+    // OAUTH/OIDC AUTH FLOW
+    // =========================================================================
+    print_verbose("🔍 Checking OAuth/OIDC auth flow...");
+    check_oauth_security(&files, &mut issues);
+
     // =========================================================================
     // REACT CVE PATTERNS
     // =========================================================================
     print_verbose("🔍 Checking React CVE patterns...");
-    check_react_cve_patterns(&files, &diff, &mut issues);
+    check_react_cve_patterns(&files, &mut issues);
 
     // =========================================================================
     // FOODSHARE SPECIFIC PATTERNS
@@ -134,7 +268,13 @@ pub fn run(files: &[String]) -> Result<()> {
     // OWASP A08:2021 - Software and Data Integrity (SRI)
     // =========================================================================
     print_verbose("🔍 OWASP A08: Checking software integrity...");
-    check_software_integrity(&files, &diff, &mut issues);
+    check_software_integrity(&files, &diff, &mut issues, verify_sri);
+
+    // =========================================================================
+    // RESPONSE HEADER HARDENING
+    // =========================================================================
+    print_verbose("🔍 Checking response header hardening...");
+    check_response_header_hardening(&files, &mut issues);
 
     // =========================================================================
     // OWASP A09:2021 - Security Logging and Monitoring
@@ -172,10 +312,55 @@ pub fn run(files: &[String]) -> Result<()> {
     print_verbose("🔍 Checking timing attack vulnerabilities...");
     check_timing_attacks(&files, &diff, &mut issues);
 
+    // =========================================================================
+    // PASSWORD HASHING STRENGTH
+    // =========================================================================
+    print_verbose("🔍 Checking password hashing cost parameters...");
+    check_password_hashing_strength(&files, &mut issues);
+
+    // =========================================================================
+    // RUNTIME TLS/HEADER POSTURE (opt-in via --scan-url)
+    // =========================================================================
+    if let Some(url) = scan_url {
+        print_verbose(&format!("🔍 Scanning deployed edge at {}...", url));
+        check_runtime_posture(url, &mut issues);
+    }
+
+    // =========================================================================
+    // CUSTOM RULE PACK (.foodrules.json / .foodrules.yaml)
+    // =========================================================================
+    print_verbose("🔍 Checking custom rule pack...");
+    check_custom_rules(&files, &mut issues);
+
     // =========================================================================
     // SUMMARY
     // =========================================================================
-    print_summary(&issues)
+    if write_baseline {
+        return write_security_baseline(&issues);
+    }
+    print_summary(&issues, thresholds)
+}
+
+/// Serializes the current finding set to `.securityignore.json`, keyed by
+/// the same fingerprint `print_summary` matches suppressions against, so
+/// every issue present today is accepted debt and only genuinely new
+/// findings fail CI afterward.
+fn write_security_baseline(issues: &[SecurityIssue]) -> Result<()> {
+    let entries: Vec<security_baseline::BaselineEntry> = issues
+        .iter()
+        .map(|issue| security_baseline::BaselineEntry {
+            fingerprint: security_baseline::fingerprint(
+                &rule_id(issue.owasp, &issue.message),
+                &issue.file,
+                &issue.message,
+            ),
+            reason: Some("recorded by --write-baseline".to_string()),
+            expires: None,
+        })
+        .collect();
+    security_baseline::write(Path::new(".securityignore.json"), &entries)?;
+    print_success(&format!("Wrote {} finding(s) to .securityignore.json", entries.len()));
+    Ok(())
 }
 
 
@@ -209,6 +394,7 @@ fn check_injection_vulnerabilities(files: &[String], diff: &str, issues: &mut Ve
                             file: file.clone(),
                             message: msg.to_string(),
                             owasp: Some("A03:2021"),
+                            line: None,
                         });
                     }
                 }
@@ -222,6 +408,7 @@ fn check_injection_vulnerabilities(files: &[String], diff: &str, issues: &mut Ve
                             file: file.clone(),
                             message: msg.to_string(),
                             owasp: Some("A03:2021"),
+                            line: None,
                         });
                     }
                 }
@@ -235,14 +422,16 @@ fn check_injection_vulnerabilities(files: &[String], diff: &str, issues: &mut Ve
         (r#"(?:SELECT|INSERT|UPDATE|DELETE).*\+\s*(?:req|params|query|body)"#, "SQL with user input concatenation"),
     ];
 
+    let added = parse_diff_added_lines(diff);
     for (pattern, msg) in &diff_patterns {
         if let Ok(re) = Regex::new(pattern) {
-            if diff.lines().filter(|l| l.starts_with('+')).any(|l| re.is_match(l)) {
+            if let Some(hit) = added.iter().find(|l| re.is_match(&l.content)) {
                 issues.push(SecurityIssue {
                     severity: Severity::Critical,
-                    file: "diff".to_string(),
+                    file: hit.file.clone(),
                     message: msg.to_string(),
                     owasp: Some("A03:2021"),
+                    line: Some(hit.line),
                 });
             }
         }
@@ -266,6 +455,7 @@ fn check_xss_vulnerabilities(files: &[String], diff: &str, issues: &mut Vec<Secu
                         file: file.clone(),
                         message: "dangerouslySetInnerHTML without sanitization library".to_string(),
                         owasp: Some("A07:2021"),
+                        line: None,
                     });
                 }
 
@@ -276,6 +466,7 @@ fn check_xss_vulnerabilities(files: &[String], diff: &str, issues: &mut Vec<Secu
                         file: file.clone(),
                         message: "innerHTML assignment without sanitization".to_string(),
                         owasp: Some("A07:2021"),
+                        line: None,
                     });
                 }
 
@@ -286,6 +477,7 @@ fn check_xss_vulnerabilities(files: &[String], diff: &str, issues: &mut Vec<Secu
                         file: file.clone(),
                         message: "document.write() is XSS-prone - avoid usage".to_string(),
                         owasp: Some("A07:2021"),
+                        line: None,
                     });
                 }
             }
@@ -294,15 +486,14 @@ fn check_xss_vulnerabilities(files: &[String], diff: &str, issues: &mut Vec<Secu
 
     // Check for href javascript: protocol
     let js_href_re = Regex::new(r#"href\s*=\s*[`'"]?\s*javascript:"#).unwrap();
-    for line in diff.lines().filter(|l| l.starts_with('+')) {
-        if js_href_re.is_match(line) {
-            issues.push(SecurityIssue {
-                severity: Severity::Critical,
-                file: "diff".to_string(),
-                message: "javascript: protocol in href - XSS vulnerability".to_string(),
-                owasp: Some("A07:2021"),
-            });
-        }
+    for hit in parse_diff_added_lines(diff).iter().filter(|l| js_href_re.is_match(&l.content)) {
+        issues.push(SecurityIssue {
+            severity: Severity::Critical,
+            file: hit.file.clone(),
+            message: "javascript: protocol in href - XSS vulnerability".to_string(),
+            owasp: Some("A07:2021"),
+            line: Some(hit.line),
+        });
     }
 }
 
@@ -329,6 +520,7 @@ fn check_ssrf_vulnerabilities(files: &[String], diff: &str, issues: &mut Vec<Sec
                                 file: file.clone(),
                                 message: msg.to_string(),
                                 owasp: Some("A10:2021"),
+                                line: None,
                             });
                         }
                     }
@@ -346,6 +538,7 @@ fn check_ssrf_vulnerabilities(files: &[String], diff: &str, issues: &mut Vec<Sec
                         file: file.clone(),
                         message: "External request without URL allowlist validation".to_string(),
                         owasp: Some("A10:2021"),
+                        line: None,
                     });
                 }
             }
@@ -353,15 +546,17 @@ fn check_ssrf_vulnerabilities(files: &[String], diff: &str, issues: &mut Vec<Sec
     }
 
     // Check diff for new SSRF patterns
-    for line in diff.lines().filter(|l| l.starts_with('+')) {
-        if line.contains("fetch(") && (line.contains("${") || line.contains("` +")) {
-            issues.push(SecurityIssue {
-                severity: Severity::High,
-                file: "diff".to_string(),
-                message: "Dynamic URL in fetch() - validate against allowlist".to_string(),
-                owasp: Some("A10:2021"),
-            });
-        }
+    for hit in parse_diff_added_lines(diff)
+        .iter()
+        .filter(|l| l.content.contains("fetch(") && (l.content.contains("${") || l.content.contains("` +")))
+    {
+        issues.push(SecurityIssue {
+            severity: Severity::High,
+            file: hit.file.clone(),
+            message: "Dynamic URL in fetch() - validate against allowlist".to_string(),
+            owasp: Some("A10:2021"),
+            line: Some(hit.line),
+        });
     }
 }
 
@@ -391,6 +586,7 @@ fn check_access_control(files: &[String], issues: &mut Vec<SecurityIssue>) {
                             file: file.clone(),
                             message: "Server Action performs mutation without authentication check".to_string(),
                             owasp: Some("A01:2021"),
+                            line: None,
                         });
                     }
                 }
@@ -413,6 +609,7 @@ fn check_access_control(files: &[String], issues: &mut Vec<SecurityIssue>) {
                         file: file.clone(),
                         message: "API route handles mutations without authentication".to_string(),
                         owasp: Some("A01:2021"),
+                        line: None,
                     });
                 }
             }
@@ -429,6 +626,7 @@ fn check_access_control(files: &[String], issues: &mut Vec<SecurityIssue>) {
                             file: file.clone(),
                             message: "Accessing resource by ID without ownership verification (potential IDOR)".to_string(),
                             owasp: Some("A01:2021"),
+                            line: None,
                         });
                     }
                 }
@@ -437,6 +635,50 @@ fn check_access_control(files: &[String], issues: &mut Vec<SecurityIssue>) {
     }
 }
 
+/// Dataflow variant of the access-control check above: rather than asking
+/// whether the file merely contains an auth-flavored substring somewhere,
+/// this resolves each individual Supabase mutation call to its enclosing
+/// Server Action/handler and checks that specific call site for a preceding
+/// identity fetch and an ownership constraint binding the mutation to it.
+/// Falls back to nothing (not a regex approximation) when the file fails to
+/// parse - `check_access_control` above already covers that file coarsely.
+fn check_mutation_authorization_dataflow(files: &[String], issues: &mut Vec<SecurityIssue>) {
+    for file in files {
+        let is_handler = file.contains("/actions/") || (file.contains("/api/") && file.ends_with("route.ts"));
+        if !is_handler {
+            continue;
+        }
+        let Ok(content) = fs::read_to_string(file) else { continue };
+        let Some(parsed) = ast_query::parse(&content) else { continue };
+
+        for gap in access_control::analyze(&parsed) {
+            if gap.missing_identity {
+                issues.push(SecurityIssue {
+                    severity: Severity::High,
+                    file: file.clone(),
+                    message: format!(
+                        "Supabase .{}() at `{}` has no preceding auth.getUser()/getSession() call - missing authentication",
+                        gap.method, gap.call_site
+                    ),
+                    owasp: Some("A01:2021"),
+                    line: Some(gap.line),
+                });
+            } else if gap.missing_ownership {
+                issues.push(SecurityIssue {
+                    severity: Severity::Critical,
+                    file: file.clone(),
+                    message: format!(
+                        "Supabase .{}() at `{}` fetches identity but isn't constrained to it (no user_id/owner filter or binding) - broken object-level authorization (IDOR)",
+                        gap.method, gap.call_site
+                    ),
+                    owasp: Some("A01:2021"),
+                    line: Some(gap.line),
+                });
+            }
+        }
+    }
+}
+
 
 // =============================================================================
 // OWASP A02:2021 - Cryptographic Failures
@@ -470,6 +712,7 @@ fn check_crypto_failures(files: &[String], diff: &str, issues: &mut Vec<Security
                             file: file.clone(),
                             message: msg.to_string(),
                             owasp: Some("A02:2021"),
+                            line: None,
                         });
                     }
                 }
@@ -489,6 +732,7 @@ fn check_crypto_failures(files: &[String], diff: &str, issues: &mut Vec<Security
                             file: file.clone(),
                             message: "Potential hardcoded secret/credential".to_string(),
                             owasp: Some("A02:2021"),
+                            line: None,
                         });
                     }
                 }
@@ -497,20 +741,141 @@ fn check_crypto_failures(files: &[String], diff: &str, issues: &mut Vec<Security
     }
 
     // Check diff for sensitive storage
+    let added = parse_diff_added_lines(diff);
     for (pattern, msg) in &sensitive_storage {
         if let Ok(re) = Regex::new(pattern) {
-            if diff.lines().filter(|l| l.starts_with('+')).any(|l| re.is_match(l)) {
+            if let Some(hit) = added.iter().find(|l| re.is_match(&l.content)) {
                 issues.push(SecurityIssue {
                     severity: Severity::High,
-                    file: "diff".to_string(),
+                    file: hit.file.clone(),
                     message: msg.to_string(),
                     owasp: Some("A02:2021"),
+                    line: Some(hit.line),
+                });
+            }
+        }
+    }
+}
+
+/// High-entropy token detection: `content.contains("password")`-style
+/// substring checks miss hardcoded API keys and tokens that don't carry a
+/// recognizable name nearby. This scans every whitespace/punctuation-
+/// delimited token in added diff lines and full file contents and flags
+/// ones whose character distribution looks like random key material rather
+/// than prose or an identifier.
+fn check_high_entropy_secrets(files: &[String], diff: &str, issues: &mut Vec<SecurityIssue>) {
+    for file in files {
+        if file.contains(".env") || file.contains("example") || file.contains("lock") {
+            continue;
+        }
+        if let Ok(content) = fs::read_to_string(file) {
+            for token in tokenize(&content) {
+                if let Some((severity, message)) = high_entropy_message(token) {
+                    issues.push(SecurityIssue {
+                        severity,
+                        file: file.clone(),
+                        message,
+                        owasp: Some("A02:2021"),
+                        line: None,
+                    });
+                }
+            }
+        }
+    }
+
+    for hit in parse_diff_added_lines(diff) {
+        for token in tokenize(&hit.content) {
+            if let Some((severity, message)) = high_entropy_message(token) {
+                issues.push(SecurityIssue {
+                    severity,
+                    file: hit.file.clone(),
+                    message,
+                    owasp: Some("A02:2021"),
+                    line: Some(hit.line),
                 });
             }
         }
     }
 }
 
+/// Splits on anything that isn't part of a base64/hex token, so e.g. a
+/// `key="AKIAabcd1234..."` literal yields the token itself rather than the
+/// surrounding quotes/assignment.
+fn tokenize(content: &str) -> impl Iterator<Item = &str> {
+    content.split(|c: char| !(c.is_ascii_alphanumeric() || c == '+' || c == '/' || c == '='))
+}
+
+/// `None` when `token` doesn't look like secret material; `Some((severity,
+/// message))` naming the charset and computed entropy otherwise.
+///
+/// Hex is scored separately from (and more conservatively than) base64: a
+/// 16-symbol alphabet caps entropy at 4.0 bits/char, and git commit SHAs
+/// (40 hex chars) and UUIDs-without-dashes (32 hex chars) sit right at that
+/// ceiling - indistinguishable from a real hex secret by entropy alone. So
+/// beyond raising the threshold, hex hits are reported as advisory `Low`
+/// rather than `High`, and the git/UUID hash lengths are excluded outright.
+fn high_entropy_message(token: &str) -> Option<(Severity, String)> {
+    if token.len() < 20 || is_placeholder(token) {
+        return None;
+    }
+
+    let is_base64_charset = token.chars().all(|c| c.is_ascii_alphanumeric() || c == '+' || c == '/' || c == '=');
+    let is_hex_charset = token.chars().all(|c| c.is_ascii_hexdigit());
+
+    let entropy = shannon_entropy(token);
+    if is_base64_charset && entropy > 4.5 {
+        Some((
+            Severity::High,
+            format!("High-entropy base64-like string (H={:.1}) - possible hardcoded API key/token", entropy),
+        ))
+    } else if is_hex_charset && entropy > 3.7 && !is_common_hash_length(token.len()) {
+        Some((
+            Severity::Low,
+            format!("High-entropy hex string (H={:.1}) - possible hardcoded key/token (could also be a commit SHA or similar non-secret hash)", entropy),
+        ))
+    } else {
+        None
+    }
+}
+
+/// Lengths of hex strings that are overwhelmingly more likely to be a commit
+/// SHA or dash-stripped UUID than a secret: git's SHA-1 (40) and SHA-256 (64)
+/// object IDs, and a UUID with its dashes removed (32).
+fn is_common_hash_length(len: usize) -> bool {
+    matches!(len, 32 | 40 | 64)
+}
+
+/// Shannon entropy `H = -Σ p_i * log2(p_i)` over `token`'s character
+/// frequency distribution, in bits per character.
+fn shannon_entropy(token: &str) -> f64 {
+    let mut counts = std::collections::HashMap::new();
+    for c in token.chars() {
+        *counts.entry(c).or_insert(0u32) += 1;
+    }
+    let len = token.len() as f64;
+    counts
+        .values()
+        .map(|&count| {
+            let p = count as f64 / len;
+            -p * p.log2()
+        })
+        .sum()
+}
+
+/// Rules out obvious non-secrets a high raw entropy score wouldn't catch on
+/// its own: a run of one repeated character, or a strictly ascending/
+/// descending run (e.g. a base64-alphabet placeholder like
+/// `ABCDEFGHIJKLMNOPQRSTUVWX`).
+fn is_placeholder(token: &str) -> bool {
+    let chars: Vec<char> = token.chars().collect();
+    if chars.windows(2).all(|w| w[0] == w[1]) {
+        return true;
+    }
+    let ascending = chars.windows(2).all(|w| (w[1] as i32) - (w[0] as i32) == 1);
+    let descending = chars.windows(2).all(|w| (w[0] as i32) - (w[1] as i32) == 1);
+    ascending || descending
+}
+
 
 // =============================================================================
 // OWASP A04:2021 - Insecure Design (Path Traversal, Open Redirect)
@@ -541,6 +906,7 @@ fn check_insecure_design(files: &[String], diff: &str, issues: &mut Vec<Security
                             file: file.clone(),
                             message: msg.to_string(),
                             owasp: Some("A04:2021"),
+                            line: None,
                         });
                     }
                 }
@@ -554,6 +920,7 @@ fn check_insecure_design(files: &[String], diff: &str, issues: &mut Vec<Security
                             file: file.clone(),
                             message: msg.to_string(),
                             owasp: Some("A04:2021"),
+                            line: None,
                         });
                     }
                 }
@@ -568,6 +935,7 @@ fn check_insecure_design(files: &[String], diff: &str, issues: &mut Vec<Security
                             file: file.clone(),
                             message: "Redirect without URL validation - validate against allowlist".to_string(),
                             owasp: Some("A04:2021"),
+                            line: None,
                         });
                     }
                 }
@@ -576,16 +944,17 @@ fn check_insecure_design(files: &[String], diff: &str, issues: &mut Vec<Security
     }
 
     // Check diff for path traversal attempts
-    for line in diff.lines().filter(|l| l.starts_with('+')) {
-        if line.contains("..") && (line.contains("path") || line.contains("file") || line.contains("fs.")) {
-            issues.push(SecurityIssue {
-                severity: Severity::High,
-                file: "diff".to_string(),
-                message: "Potential path traversal pattern in new code".to_string(),
-                owasp: Some("A04:2021"),
-            });
-            break;
-        }
+    if let Some(hit) = parse_diff_added_lines(diff)
+        .iter()
+        .find(|l| l.content.contains("..") && (l.content.contains("path") || l.content.contains("file") || l.content.contains("fs.")))
+    {
+        issues.push(SecurityIssue {
+            severity: Severity::High,
+            file: hit.file.clone(),
+            message: "Potential path traversal pattern in new code".to_string(),
+            owasp: Some("A04:2021"),
+            line: Some(hit.line),
+        });
     }
 }
 
@@ -593,6 +962,18 @@ fn check_insecure_design(files: &[String], diff: &str, issues: &mut Vec<Security
 // =============================================================================
 // OWASP A05:2021 - Security Misconfiguration
 // =============================================================================
+/// Reads whichever `next.config.*` exists at the project root, regardless
+/// of whether it's among the files staged for this commit, so Server Action
+/// origin checks see the project-wide allowlist rather than only firing
+/// when next.config itself happens to be part of the diff.
+fn read_next_config() -> Option<String> {
+    ["next.config.js", "next.config.mjs", "next.config.ts"]
+        .iter()
+        .map(Path::new)
+        .find(|p| p.exists())
+        .and_then(|p| fs::read_to_string(p).ok())
+}
+
 fn check_security_config(files: &[String], issues: &mut Vec<SecurityIssue>) {
     for file in files {
         // Check next.config.ts/js for security headers
@@ -613,6 +994,7 @@ fn check_security_config(files: &[String], issues: &mut Vec<SecurityIssue>) {
                                 file: file.clone(),
                                 message: msg.to_string(),
                                 owasp: Some("A05:2021"),
+                                line: None,
                             });
                         }
                     }
@@ -622,6 +1004,7 @@ fn check_security_config(files: &[String], issues: &mut Vec<SecurityIssue>) {
                         file: file.clone(),
                         message: "No security headers configured in next.config".to_string(),
                         owasp: Some("A05:2021"),
+                        line: None,
                     });
                 }
 
@@ -632,6 +1015,7 @@ fn check_security_config(files: &[String], issues: &mut Vec<SecurityIssue>) {
                         file: file.clone(),
                         message: "dangerouslyAllowSVG enabled - SVGs can contain scripts".to_string(),
                         owasp: Some("A05:2021"),
+                        line: None,
                     });
                 }
 
@@ -641,6 +1025,7 @@ fn check_security_config(files: &[String], issues: &mut Vec<SecurityIssue>) {
                         file: file.clone(),
                         message: "ignoreBuildErrors enabled - may hide security issues".to_string(),
                         owasp: Some("A05:2021"),
+                        line: None,
                     });
                 }
             }
@@ -655,6 +1040,7 @@ fn check_security_config(files: &[String], issues: &mut Vec<SecurityIssue>) {
                         file: file.clone(),
                         message: "Consider adding security headers in vercel.json".to_string(),
                         owasp: Some("A05:2021"),
+                        line: None,
                     });
                 }
             }
@@ -671,6 +1057,7 @@ fn check_security_config(files: &[String], issues: &mut Vec<SecurityIssue>) {
                             file: file.clone(),
                             message: "Ensure debug/development mode is disabled in production".to_string(),
                             owasp: Some("A05:2021"),
+                            line: None,
                         });
                     }
                 }
@@ -694,6 +1081,7 @@ fn check_supply_chain(files: &[String], diff: &str, issues: &mut Vec<SecurityIss
                 file: file.clone(),
                 message: "Lock file modified - verify dependency changes are intentional".to_string(),
                 owasp: Some("A06:2021"),
+                line: None,
             });
         }
     }
@@ -721,26 +1109,7 @@ fn check_supply_chain(files: &[String], diff: &str, issues: &mut Vec<SecurityIss
                             file: file.clone(),
                             message: format!("Potential typosquatting: '{}' - did you mean '{}'?", typo, correct),
                             owasp: Some("A06:2021"),
-                        });
-                    }
-                }
-
-                // Check for known malicious packages
-                let malicious_packages = [
-                    "event-stream", // CVE-2018-16487
-                    "flatmap-stream",
-                    "ua-parser-js", // Check version
-                    "coa", // Compromised
-                    "rc", // Compromised
-                ];
-
-                for pkg in &malicious_packages {
-                    if content.contains(&format!("\"{}\"", pkg)) {
-                        issues.push(SecurityIssue {
-                            severity: Severity::High,
-                            file: file.clone(),
-                            message: format!("Package '{}' has known security incidents - verify version", pkg),
-                            owasp: Some("A06:2021"),
+                            line: None,
                         });
                     }
                 }
@@ -748,30 +1117,202 @@ fn check_supply_chain(files: &[String], diff: &str, issues: &mut Vec<SecurityIss
         }
     }
 
+    if files.iter().any(|f| f.ends_with("package.json") || lock_files.iter().any(|lf| f.ends_with(lf))) {
+        run_osv_advisory_scan(issues);
+    }
+
     // Check diff for new dependencies from untrusted sources
-    for line in diff.lines().filter(|l| l.starts_with('+')) {
+    for hit in parse_diff_added_lines(diff) {
         // GitHub/GitLab direct dependencies
-        if line.contains("github:") || line.contains("git+") || line.contains("git://") {
+        if hit.content.contains("github:") || hit.content.contains("git+") || hit.content.contains("git://") {
             issues.push(SecurityIssue {
                 severity: Severity::Medium,
-                file: "diff".to_string(),
+                file: hit.file.clone(),
                 message: "Git-based dependency added - prefer npm registry packages".to_string(),
                 owasp: Some("A06:2021"),
+                line: Some(hit.line),
             });
         }
 
         // HTTP (non-HTTPS) dependencies
-        if line.contains("http://") && !line.contains("localhost") {
+        if hit.content.contains("http://") && !hit.content.contains("localhost") {
             issues.push(SecurityIssue {
                 severity: Severity::High,
-                file: "diff".to_string(),
+                file: hit.file.clone(),
                 message: "HTTP dependency URL - use HTTPS only".to_string(),
                 owasp: Some("A06:2021"),
+                line: Some(hit.line),
             });
         }
     }
+
+    if files.iter().any(|f| f.ends_with("package.json")) {
+        run_dependency_audit(issues);
+    }
+}
+
+/// Shells out to the project's package manager audit command and maps each
+/// reported advisory onto a `SecurityIssue`, cross-referenced against the
+/// `[supply_chain].allowlist` entries in `.foodcheck.toml` so an accepted
+/// transitive risk can be waived by advisory id/URL instead of blocking
+/// every future commit. Degrades silently when the audit command isn't
+/// installed or its output isn't the `vulnerabilities` map shape we expect,
+/// since that shouldn't block the rest of the scan.
+fn run_dependency_audit(issues: &mut Vec<SecurityIssue>) {
+    let manager = if Path::new("pnpm-lock.yaml").exists() {
+        "pnpm"
+    } else if Path::new("yarn.lock").exists() {
+        "yarn"
+    } else {
+        "npm"
+    };
+
+    let Ok(output) = Command::new(manager).args(["audit", "--json"]).output() else {
+        return;
+    };
+
+    let Ok(report) = serde_json::from_slice::<serde_json::Value>(&output.stdout) else {
+        return;
+    };
+
+    let Some(vulnerabilities) = report.get("vulnerabilities").and_then(|v| v.as_object()) else {
+        return;
+    };
+
+    let config = FoodcheckConfig::load(Path::new(".")).unwrap_or_default();
+    let allowlist = config.globs_for("supply_chain").allowlist;
+
+    for (name, advisory) in vulnerabilities {
+        let severity_str = advisory.get("severity").and_then(|s| s.as_str()).unwrap_or("low");
+        let advisory_ids: Vec<String> = advisory
+            .get("via")
+            .and_then(|v| v.as_array())
+            .into_iter()
+            .flatten()
+            .filter_map(|v| v.as_object())
+            .filter_map(|o| o.get("url").and_then(|u| u.as_str()))
+            .map(String::from)
+            .collect();
+
+        let waived = allowlist
+            .iter()
+            .any(|entry| entry == name || advisory_ids.iter().any(|id| id.contains(entry.as_str())));
+        if waived {
+            continue;
+        }
+
+        let severity = match severity_str {
+            "critical" => Severity::Critical,
+            "high" => Severity::High,
+            "moderate" => Severity::Medium,
+            _ => Severity::Low,
+        };
+
+        let message = if advisory_ids.is_empty() {
+            format!("{}: {} severity vulnerability (run `{} audit` for details)", name, severity_str, manager)
+        } else {
+            format!("{}: {} severity vulnerability ({})", name, severity_str, advisory_ids.join(", "))
+        };
+
+        issues.push(SecurityIssue {
+            severity,
+            file: "package.json".to_string(),
+            message,
+            owasp: Some("A06:2021"),
+            line: None,
+        });
+    }
+}
+
+/// Resolves every locked dependency's actual installed version against the
+/// OSV database, replacing the old hardcoded "names we remembered" list with
+/// real per-version CVE matching. Reads `.osv-cache.json` (a saved
+/// `querybatch` response) when present so CI stays deterministic and
+/// offline; otherwise queries the live API. Degrades silently on any error
+/// (no lockfile, no network, malformed cache) since a failed advisory
+/// lookup shouldn't block the rest of the scan.
+fn run_osv_advisory_scan(issues: &mut Vec<SecurityIssue>) {
+    let packages = osv_advisories::locked_packages(Path::new("."));
+    if packages.is_empty() {
+        return;
+    }
+
+    let cache = Path::new(".osv-cache.json");
+    let offline_cache = cache.exists().then_some(cache);
+
+    let Ok(advisories) = osv_advisories::query_advisories(&packages, offline_cache) else {
+        return;
+    };
+
+    for advisory in advisories {
+        let severity = match advisory.severity.as_deref() {
+            Some("CRITICAL") => Severity::Critical,
+            Some("HIGH") => Severity::High,
+            Some("MODERATE") | Some("MEDIUM") => Severity::Medium,
+            _ => Severity::Low,
+        };
+
+        issues.push(SecurityIssue {
+            severity,
+            file: "package.json".to_string(),
+            message: format!("{}@{}: {} ({})", advisory.package, advisory.version, advisory.summary, advisory.id),
+            owasp: Some("A06:2021"),
+            line: None,
+        });
+    }
+}
+
+/// Runs every rule from `.foodrules.json`/`.foodrules.yaml` (see
+/// [`rule_pack`]) against each scanned file, merging project-specific or
+/// tuned-to-silence-a-false-positive rules in alongside the compiled-in
+/// `*_patterns` checks above. A missing or empty rule pack is a no-op.
+fn check_custom_rules(files: &[String], issues: &mut Vec<SecurityIssue>) {
+    let rules = match rule_pack::load(Path::new(".")) {
+        Ok(rules) => rules,
+        Err(err) => {
+            print_warning(&format!("Skipping custom rule pack: {}", err));
+            return;
+        }
+    };
+
+    if rules.is_empty() {
+        return;
+    }
+
+    for file in files {
+        let Ok(content) = fs::read_to_string(file) else {
+            continue;
+        };
+
+        for rule in rules.iter().filter(|r| r.applies_to(file)) {
+            if rule.is_match(&content) {
+                issues.push(SecurityIssue {
+                    severity: severity_from_rule(rule),
+                    file: file.clone(),
+                    message: format!("{} ({})", rule.message, rule.id),
+                    owasp: rule.owasp.as_deref().map(owasp_leak),
+                    line: None,
+                });
+            }
+        }
+    }
+}
+
+fn severity_from_rule(rule: &Rule) -> Severity {
+    match rule.severity.to_lowercase().as_str() {
+        "critical" => Severity::Critical,
+        "high" => Severity::High,
+        "medium" | "moderate" => Severity::Medium,
+        _ => Severity::Low,
+    }
 }
 
+/// `SecurityIssue::owasp` is `&'static str`, but a rule pack's tag is parsed
+/// at runtime from config - leak it once rather than threading an owned
+/// `String` through a field every other check treats as `'static`.
+fn owasp_leak(s: &str) -> &'static str {
+    Box::leak(s.to_string().into_boxed_str())
+}
 
 // =============================================================================
 // RUNTIME SECURITY - eval, Function constructor, prototype pollution
@@ -801,6 +1342,7 @@ fn check_runtime_security(files: &[String], diff: &str, issues: &mut Vec<Securit
                             file: file.clone(),
                             message: msg.to_string(),
                             owasp: Some("A03:2021"),
+                            line: None,
                         });
                     }
                 }
@@ -814,6 +1356,7 @@ fn check_runtime_security(files: &[String], diff: &str, issues: &mut Vec<Securit
                             file: file.clone(),
                             message: msg.to_string(),
                             owasp: Some("A03:2021"),
+                            line: None,
                         });
                     }
                 }
@@ -828,6 +1371,7 @@ fn check_runtime_security(files: &[String], diff: &str, issues: &mut Vec<Securit
                             file: file.clone(),
                             message: "JSON.parse without try-catch - can crash on malformed input".to_string(),
                             owasp: Some("A03:2021"),
+                            line: None,
                         });
                     }
                 }
@@ -836,16 +1380,17 @@ fn check_runtime_security(files: &[String], diff: &str, issues: &mut Vec<Securit
     }
 
     // Check diff for new dangerous patterns
-    for line in diff.lines().filter(|l| l.starts_with('+')) {
-        if line.contains("eval(") || line.contains("new Function(") {
-            issues.push(SecurityIssue {
-                severity: Severity::Critical,
-                file: "diff".to_string(),
-                message: "Dynamic code execution added - review carefully".to_string(),
-                owasp: Some("A03:2021"),
-            });
-            break;
-        }
+    if let Some(hit) = parse_diff_added_lines(diff)
+        .iter()
+        .find(|l| l.content.contains("eval(") || l.content.contains("new Function("))
+    {
+        issues.push(SecurityIssue {
+            severity: Severity::Critical,
+            file: hit.file.clone(),
+            message: "Dynamic code execution added - review carefully".to_string(),
+            owasp: Some("A03:2021"),
+            line: Some(hit.line),
+        });
     }
 }
 
@@ -855,6 +1400,18 @@ fn check_runtime_security(files: &[String], diff: &str, issues: &mut Vec<Securit
 // =============================================================================
 fn check_nextjs_patterns(files: &[String], issues: &mut Vec<SecurityIssue>) {
     let server_action_re = Regex::new(r#"['"]use server['"]"#).unwrap();
+    let next_config = read_next_config();
+    let allowed_origins_configured = next_config.as_deref().is_some_and(|content| content.contains("allowedOrigins"));
+    // Next.js Server Actions already compare the request's Origin against its
+    // own Host header by default (since 13.4) - `allowedOrigins` is only
+    // needed when the app sits behind a reverse proxy or serves multiple
+    // domains, where the Host Next.js sees no longer matches what users hit.
+    // Without evidence of that setup, flagging every mutating Server Action
+    // for missing `allowedOrigins` is a false positive against a safe
+    // default app.
+    let behind_proxy_or_multi_domain = next_config
+        .as_deref()
+        .is_some_and(|content| content.contains("basePath") || content.contains("assetPrefix") || content.contains("trustHost"));
 
     for file in files {
         if let Ok(content) = fs::read_to_string(file) {
@@ -871,6 +1428,46 @@ fn check_nextjs_patterns(files: &[String], issues: &mut Vec<SecurityIssue>) {
                         file: file.clone(),
                         message: "Server Action mutates without cache invalidation".to_string(),
                         owasp: None,
+                        line: None,
+                    });
+                }
+
+                // Server Action CSRF: only relevant behind a reverse proxy or
+                // multi-domain setup, where the Host header Next.js checks
+                // against no longer reflects the origin users actually hit.
+                if has_mutation && behind_proxy_or_multi_domain && !allowed_origins_configured {
+                    issues.push(SecurityIssue {
+                        severity: Severity::High,
+                        file: file.clone(),
+                        message: "Server Action mutates without `experimental.serverActions.allowedOrigins` configured in next.config - next.config's basePath/assetPrefix/trustHost suggests this app sits behind a reverse proxy or multiple domains, where Next.js's default Origin/Host check isn't enough".to_string(),
+                        owasp: Some("A01:2021"),
+                        line: None,
+                    });
+                }
+            }
+
+            // Server Actions that read Origin/Referer/X-Forwarded-Host
+            // directly to make a trust decision, instead of comparing
+            // against the configured allowlist, are as vulnerable to CSRF
+            // as having no allowlist at all.
+            if server_action_re.is_match(&content) {
+                let trusts_forwarded_header = content.contains("headers().get(\"origin\")")
+                    || content.contains("headers().get('origin')")
+                    || content.contains("headers().get(\"referer\")")
+                    || content.contains("headers().get('referer')")
+                    || content.contains("headers().get(\"x-forwarded-host\")")
+                    || content.contains("headers().get('x-forwarded-host')");
+
+                if trusts_forwarded_header
+                    && !content.contains("allowedOrigins")
+                    && !content.contains("ALLOWED_ORIGINS")
+                {
+                    issues.push(SecurityIssue {
+                        severity: Severity::High,
+                        file: file.clone(),
+                        message: "Server Action trusts Origin/Referer/X-Forwarded-Host header directly - compare against a configured allowlist, not the header value alone".to_string(),
+                        owasp: Some("A01:2021"),
+                        line: None,
                     });
                 }
             }
@@ -883,6 +1480,7 @@ fn check_nextjs_patterns(files: &[String], issues: &mut Vec<SecurityIssue>) {
                         file: file.clone(),
                         message: "Server Action lacks input validation - use zod or similar".to_string(),
                         owasp: Some("A03:2021"),
+                        line: None,
                     });
                 }
             }
@@ -895,6 +1493,7 @@ fn check_nextjs_patterns(files: &[String], issues: &mut Vec<SecurityIssue>) {
                         file: file.clone(),
                         message: "Server Action may be returning sensitive data to client".to_string(),
                         owasp: Some("A01:2021"),
+                        line: None,
                     });
                 }
             }
@@ -907,6 +1506,7 @@ fn check_nextjs_patterns(files: &[String], issues: &mut Vec<SecurityIssue>) {
                         file: file.clone(),
                         message: "Sensitive data passed as props - may be serialized to client".to_string(),
                         owasp: Some("A01:2021"),
+                        line: None,
                     });
                 }
             }
@@ -919,6 +1519,7 @@ fn check_nextjs_patterns(files: &[String], issues: &mut Vec<SecurityIssue>) {
                         file: file.clone(),
                         message: "generateMetadata with params - ensure proper escaping for SEO injection".to_string(),
                         owasp: Some("A03:2021"),
+                        line: None,
                     });
                 }
             }
@@ -943,6 +1544,7 @@ fn check_vercel_security(files: &[String], issues: &mut Vec<SecurityIssue>) {
                             file: file.clone(),
                             message: "Middleware matcher may not exclude internal paths (_next, api)".to_string(),
                             owasp: Some("A01:2021"),
+                            line: None,
                         });
                     }
                 }
@@ -955,6 +1557,7 @@ fn check_vercel_security(files: &[String], issues: &mut Vec<SecurityIssue>) {
                             file: file.clone(),
                             message: "Middleware passes all requests - add authentication checks".to_string(),
                             owasp: Some("A01:2021"),
+                            line: None,
                         });
                     }
                 }
@@ -967,6 +1570,7 @@ fn check_vercel_security(files: &[String], issues: &mut Vec<SecurityIssue>) {
                             file: file.clone(),
                             message: "Middleware redirect with user URL - validate destination".to_string(),
                             owasp: Some("A04:2021"),
+                            line: None,
                         });
                     }
                 }
@@ -984,6 +1588,7 @@ fn check_vercel_security(files: &[String], issues: &mut Vec<SecurityIssue>) {
                             file: file.clone(),
                             message: "Edge runtime cannot use Node.js APIs (fs, child_process)".to_string(),
                             owasp: None,
+                            line: None,
                         });
                     }
 
@@ -994,6 +1599,7 @@ fn check_vercel_security(files: &[String], issues: &mut Vec<SecurityIssue>) {
                             file: file.clone(),
                             message: "Edge runtime: use Web Crypto API instead of Node crypto".to_string(),
                             owasp: None,
+                            line: None,
                         });
                     }
                 }
@@ -1006,6 +1612,7 @@ fn check_vercel_security(files: &[String], issues: &mut Vec<SecurityIssue>) {
                             file: file.clone(),
                             message: "API route lacks rate limiting - consider adding protection".to_string(),
                             owasp: Some("A04:2021"),
+                            line: None,
                         });
                     }
                 }
@@ -1022,6 +1629,7 @@ fn check_vercel_security(files: &[String], issues: &mut Vec<SecurityIssue>) {
                         file: file.clone(),
                         message: "Wildcard CORS origin - restrict to specific domains".to_string(),
                         owasp: Some("A05:2021"),
+                        line: None,
                     });
                 }
 
@@ -1032,6 +1640,7 @@ fn check_vercel_security(files: &[String], issues: &mut Vec<SecurityIssue>) {
                         file: file.clone(),
                         message: "Source maps enabled - may expose source code in production".to_string(),
                         owasp: Some("A05:2021"),
+                        line: None,
                     });
                 }
             }
@@ -1040,13 +1649,108 @@ fn check_vercel_security(files: &[String], issues: &mut Vec<SecurityIssue>) {
 }
 
 
+// =============================================================================
+// OAUTH/OIDC AUTH FLOW - redirect URIs, token storage, state/nonce
+// =============================================================================
+/// A coherent auth subsystem alongside `check_nextjs_patterns` and
+/// `check_vercel_security`: those only touch login/token handling
+/// incidentally, so this targets OIDC/OAuth login flow concerns
+/// specifically - redirect_uri allowlisting, token storage, and
+/// state/nonce on the authorize call. JWT signature/algorithm issues live
+/// in `check_jwt_verification_calls` and `check_jwt_security`'s
+/// `weak_algorithms` instead; duplicating those substring checks here only
+/// inflated the same root cause into multiple findings.
+fn check_oauth_security(files: &[String], issues: &mut Vec<SecurityIssue>) {
+    let token_storage_re = Regex::new(r#"(?i)(local|session)Storage\.setItem\(\s*['"][^'"]*(access_token|id_token|refresh_token|[_-]?token)"#).unwrap();
+
+    for file in files {
+        if let Ok(content) = fs::read_to_string(file) {
+            // redirect_uri built from request query params, used without
+            // being checked against a configured allowlist
+            let builds_redirect_uri_from_request = content.contains("redirect_uri")
+                && (content.contains("searchParams.get(") || content.contains("req.query") || content.contains("params.get("));
+            if builds_redirect_uri_from_request
+                && !content.contains("ALLOWED_REDIRECT")
+                && !content.contains("allowedRedirect")
+                && !content.contains("REDIRECT_ALLOWLIST")
+            {
+                issues.push(SecurityIssue {
+                    severity: Severity::High,
+                    file: file.clone(),
+                    message: "OAuth redirect_uri built from request query params without matching against a configured allowlist".to_string(),
+                    owasp: Some("A01:2021"),
+                    line: None,
+                });
+            }
+
+            // Access/ID tokens belong in httpOnly cookies, not Web Storage
+            if token_storage_re.is_match(&content) {
+                issues.push(SecurityIssue {
+                    severity: Severity::High,
+                    file: file.clone(),
+                    message: "OAuth/JWT token written to localStorage/sessionStorage - use an httpOnly cookie instead".to_string(),
+                    owasp: Some("A07:2021"),
+                    line: None,
+                });
+            }
+
+            // OAuth authorize call missing state/nonce (CSRF on the login flow)
+            let is_authorize_call = content.contains("/authorize")
+                && (content.contains("client_id") || content.contains("response_type"));
+            if is_authorize_call && !content.contains("state=") && !content.contains("nonce=") {
+                issues.push(SecurityIssue {
+                    severity: Severity::Medium,
+                    file: file.clone(),
+                    message: "OAuth authorize call has no state/nonce parameter - vulnerable to CSRF on the login flow".to_string(),
+                    owasp: Some("A01:2021"),
+                    line: None,
+                });
+            }
+        }
+    }
+}
+
 // =============================================================================
 // REACT CVE PATTERNS
 // =============================================================================
-fn check_react_cve_patterns(files: &[String], diff: &str, issues: &mut Vec<SecurityIssue>) {
+fn check_react_cve_patterns(files: &[String], issues: &mut Vec<SecurityIssue>) {
     for file in files {
         if file.ends_with(".tsx") || file.ends_with(".jsx") {
             if let Ok(content) = fs::read_to_string(file) {
+                // Genuine JSX target="_blank" elements, via the AST so a
+                // `rel` attribute on an unrelated element (or the phrase
+                // sitting in a comment) can't suppress/trigger a finding.
+                // Falls back to a regex over the whole file content below
+                // when the file fails to parse.
+                let parsed = ast_query::parse(&content);
+                match &parsed {
+                    Some(parsed) => {
+                        for line in parsed.unsafe_target_blank_lines() {
+                            issues.push(SecurityIssue {
+                                severity: Severity::Low,
+                                file: file.clone(),
+                                message: "target=\"_blank\" without rel=\"noopener noreferrer\" - tabnabbing risk".to_string(),
+                                owasp: Some("A05:2021"),
+                                line: Some(line),
+                            });
+                        }
+                    }
+                    None => {
+                        if Regex::new(r#"target\s*=\s*["']_blank["']"#).unwrap().is_match(&content)
+                            && !content.contains("noopener")
+                            && !content.contains("noreferrer")
+                        {
+                            issues.push(SecurityIssue {
+                                severity: Severity::Low,
+                                file: file.clone(),
+                                message: "target=\"_blank\" without rel=\"noopener noreferrer\" - tabnabbing risk".to_string(),
+                                owasp: Some("A05:2021"),
+                                line: None,
+                            });
+                        }
+                    }
+                }
+
                 // CVE-2021-27913: react-native-web XSS via style prop
                 if content.contains("react-native-web") && content.contains("style=") {
                     if content.contains("${") || content.contains("` +") {
@@ -1055,6 +1759,7 @@ fn check_react_cve_patterns(files: &[String], diff: &str, issues: &mut Vec<Secur
                             file: file.clone(),
                             message: "CVE-2021-27913: Dynamic styles in react-native-web can lead to XSS".to_string(),
                             owasp: Some("A07:2021"),
+                            line: None,
                         });
                     }
                 }
@@ -1068,6 +1773,7 @@ fn check_react_cve_patterns(files: &[String], diff: &str, issues: &mut Vec<Secur
                         file: file.clone(),
                         message: "Dynamic component rendering - ensure component name is validated".to_string(),
                         owasp: Some("A03:2021"),
+                        line: None,
                     });
                 }
 
@@ -1080,17 +1786,27 @@ fn check_react_cve_patterns(files: &[String], diff: &str, issues: &mut Vec<Secur
                             file: file.clone(),
                             message: "Prop spreading on form elements - may allow attribute injection".to_string(),
                             owasp: Some("A03:2021"),
+                            line: None,
                         });
                     }
                 }
 
-                // 3. useEffect with external URLs
-                if content.contains("useEffect") && content.contains("fetch(") {
+                // 3. useEffect with external URLs - a genuine `useEffect(...)`
+                // call expression via the AST, so the word sitting in a
+                // comment or string doesn't pair with an unrelated `fetch(`
+                // elsewhere in the file. Falls back to the old substring
+                // check when the file fails to parse.
+                let has_use_effect = match &parsed {
+                    Some(p) => !p.call_expression_lines(&["useEffect"]).is_empty(),
+                    None => content.contains("useEffect"),
+                };
+                if has_use_effect && content.contains("fetch(") {
                     issues.push(SecurityIssue {
                         severity: Severity::Medium,
                         file: file.clone(),
                         message: "Client-side fetch in useEffect - prefer Server Components for data fetching".to_string(),
                         owasp: None,
+                        line: None,
                     });
                 }
 
@@ -1101,25 +1817,16 @@ fn check_react_cve_patterns(files: &[String], diff: &str, issues: &mut Vec<Secur
                         file: file.clone(),
                         message: "Direct innerHTML via ref - use dangerouslySetInnerHTML with sanitization".to_string(),
                         owasp: Some("A07:2021"),
+                        line: None,
                     });
                 }
             }
         }
     }
 
-    // Check diff for React-specific issues
-    for line in diff.lines().filter(|l| l.starts_with('+')) {
-        // Unsafe target="_blank" without rel="noopener"
-        if line.contains("target=\"_blank\"") && !line.contains("noopener") && !line.contains("noreferrer") {
-            issues.push(SecurityIssue {
-                severity: Severity::Low,
-                file: "diff".to_string(),
-                message: "target=\"_blank\" without rel=\"noopener noreferrer\" - tabnabbing risk".to_string(),
-                owasp: Some("A05:2021"),
-            });
-            break;
-        }
-    }
+    // `target="_blank"` is now caught per-file above (AST-backed, with a
+    // regex fallback) rather than only in the diff, so it also catches an
+    // unsafe element that predates this commit.
 }
 
 
@@ -1129,8 +1836,17 @@ fn check_react_cve_patterns(files: &[String], diff: &str, issues: &mut Vec<Secur
 fn check_foodshare_patterns(files: &[String], issues: &mut Vec<SecurityIssue>) {
     for file in files {
         if let Ok(content) = fs::read_to_string(file) {
+            let parsed = ast_query::parse(&content);
+            // `'use client'` only as a genuine top-of-file directive
+            // prologue entry, via the AST - falls back to the old substring
+            // check when the file fails to parse.
+            let is_client_component = parsed
+                .as_ref()
+                .map(|p| p.has_top_of_file_directive("use client"))
+                .unwrap_or_else(|| content.contains("use client"));
+
             // Supabase server client must be awaited
-            if !content.contains("use client") {
+            if !is_client_component {
                 if content.contains("createClient()")
                     && !content.contains("await createClient()")
                     && content.contains("supabase/server")
@@ -1140,17 +1856,19 @@ fn check_foodshare_patterns(files: &[String], issues: &mut Vec<SecurityIssue>) {
                         file: file.clone(),
                         message: "Missing await on server createClient() - will fail at runtime".to_string(),
                         owasp: None,
+                        line: None,
                     });
                 }
             }
 
             // Client component should not import server client
-            if content.contains("use client") && content.contains("supabase/server") {
+            if is_client_component && content.contains("supabase/server") {
                 issues.push(SecurityIssue {
                     severity: Severity::Critical,
                     file: file.clone(),
                     message: "Server Supabase client imported in client component - use @/lib/supabase/client".to_string(),
                     owasp: Some("A01:2021"),
+                    line: None,
                 });
             }
 
@@ -1164,6 +1882,7 @@ fn check_foodshare_patterns(files: &[String], issues: &mut Vec<SecurityIssue>) {
                     file: file.clone(),
                     message: "TanStack Query detected - use Server Components for data fetching".to_string(),
                     owasp: None,
+                    line: None,
                 });
             }
 
@@ -1173,21 +1892,30 @@ fn check_foodshare_patterns(files: &[String], issues: &mut Vec<SecurityIssue>) {
                     file: file.clone(),
                     message: "Redux detected - use Zustand for UI state only".to_string(),
                     owasp: None,
+                    line: None,
                 });
             }
 
-            // Hooks require 'use client' directive
-            let hooks_re = Regex::new(r"\b(useState|useEffect|useRef|useCallback|useMemo)\s*\(").unwrap();
+            // Hooks require 'use client' directive - matched as genuine call
+            // expressions via the AST, so `useEffect` sitting in a comment
+            // or string doesn't trigger a false positive. Falls back to the
+            // old substring regex when the file fails to parse.
+            let hook_names = ["useState", "useEffect", "useRef", "useCallback", "useMemo"];
+            let uses_hooks = match &parsed {
+                Some(p) => !p.call_expression_lines(&hook_names).is_empty(),
+                None => Regex::new(r"\b(useState|useEffect|useRef|useCallback|useMemo)\s*\(").unwrap().is_match(&content),
+            };
             if (file.ends_with(".tsx") || file.ends_with(".jsx"))
                 && !file.contains("/hooks/")
-                && !content.contains("use client")
-                && hooks_re.is_match(&content)
+                && !is_client_component
+                && uses_hooks
             {
                 issues.push(SecurityIssue {
                     severity: Severity::High,
                     file: file.clone(),
                     message: "React hooks used without 'use client' directive".to_string(),
                     owasp: None,
+                    line: None,
                 });
             }
 
@@ -1196,18 +1924,19 @@ fn check_foodshare_patterns(files: &[String], issues: &mut Vec<SecurityIssue>) {
                 r"(?i)(SUPABASE_SERVICE_ROLE|DATABASE_URL|SECRET_KEY|PRIVATE_KEY|API_SECRET)",
             ).unwrap();
 
-            if content.contains("use client") && server_env_re.is_match(&content) {
+            if is_client_component && server_env_re.is_match(&content) {
                 issues.push(SecurityIssue {
                     severity: Severity::Critical,
                     file: file.clone(),
                     message: "Server-only env var referenced in client component".to_string(),
                     owasp: Some("A01:2021"),
+                    line: None,
                 });
             }
 
             // Check NEXT_PUBLIC_ prefix in client components
             let env_re = Regex::new(r"process\.env\.([A-Z][A-Z0-9_]*)").unwrap();
-            if content.contains("use client") {
+            if is_client_component {
                 for cap in env_re.captures_iter(&content) {
                     if let Some(var) = cap.get(1) {
                         let name = var.as_str();
@@ -1217,6 +1946,7 @@ fn check_foodshare_patterns(files: &[String], issues: &mut Vec<SecurityIssue>) {
                                 file: file.clone(),
                                 message: format!("process.env.{} needs NEXT_PUBLIC_ prefix for client access", name),
                                 owasp: Some("A01:2021"),
+                                line: None,
                             });
                         }
                     }
@@ -1234,6 +1964,7 @@ fn check_foodshare_patterns(files: &[String], issues: &mut Vec<SecurityIssue>) {
                     file: file.clone(),
                     message: "Use next/image instead of <img> for optimization and security".to_string(),
                     owasp: None,
+                    line: None,
                 });
             }
         }
@@ -1244,33 +1975,83 @@ fn check_foodshare_patterns(files: &[String], issues: &mut Vec<SecurityIssue>) {
 // =============================================================================
 // OWASP A08:2021 - Software and Data Integrity (SRI)
 // =============================================================================
-fn check_software_integrity(files: &[String], diff: &str, issues: &mut Vec<SecurityIssue>) {
-    for file in files {
-        if file.ends_with(".tsx") || file.ends_with(".jsx") || file.ends_with(".html") {
-            if let Ok(content) = fs::read_to_string(file) {
-                // Check for external scripts without SRI
-                let script_re = Regex::new(r#"<script[^>]+src\s*=\s*["']https?://"#).unwrap();
-                let integrity_re = Regex::new(r#"integrity\s*=\s*["']sha"#).unwrap();
-                
-                if script_re.is_match(&content) && !integrity_re.is_match(&content) {
+/// For each `<tag ... attr="https://...">` in `content`: when it has no
+/// `integrity` attribute, fetches the referenced bytes (opt-in, see
+/// `verify_sri`) and suggests a ready-to-paste `sha384-` value; when it
+/// already has one, re-fetches and verifies the strongest listed algorithm
+/// against the declared digest, flagging a mismatch as likely supply-chain
+/// tampering. Degrades to the plain "missing SRI" warning when the bytes
+/// can't be fetched (no vendored copy and `verify_sri` is off, or the fetch
+/// failed), so an offline/CI run still gets useful signal.
+fn check_tag_integrity(
+    content: &str,
+    tag: &str,
+    attr: &str,
+    missing_severity: Severity,
+    file: &str,
+    issues: &mut Vec<SecurityIssue>,
+    verify_sri: bool,
+) {
+    let tag_re = Regex::new(&format!(
+        r#"<{tag}[^>]+{attr}\s*=\s*["'](https?://[^"']+)["'][^>]*>"#
+    ))
+    .unwrap();
+    let integrity_re = Regex::new(r#"integrity\s*=\s*["']([^"']+)["']"#).unwrap();
+
+    for caps in tag_re.captures_iter(content) {
+        let tag_text = &caps[0];
+        let url = &caps[1];
+
+        match integrity_re.captures(tag_text) {
+            None => {
+                let suggestion = sri::fetch_bytes(url, verify_sri).and_then(|bytes| sri::suggest_attribute(&bytes));
+                let message = match suggestion {
+                    Some(attr) => format!("External {tag} without SRI hash - add {attr}"),
+                    None => format!("External {tag} without SRI (Subresource Integrity) hash"),
+                };
+                issues.push(SecurityIssue {
+                    severity: missing_severity,
+                    file: file.to_string(),
+                    message,
+                    owasp: Some("A08:2021"),
+                    line: None,
+                });
+            }
+            Some(integrity_caps) => {
+                let tokens = sri::parse_integrity(&integrity_caps[1]);
+                let Some(strongest) = sri::strongest(&tokens) else {
+                    continue;
+                };
+                let Some(bytes) = sri::fetch_bytes(url, verify_sri) else {
+                    continue;
+                };
+                let Some(computed) = sri::digest_base64(&strongest.algorithm, &bytes) else {
+                    continue;
+                };
+
+                if computed != strongest.digest_b64 {
                     issues.push(SecurityIssue {
-                        severity: Severity::Medium,
-                        file: file.clone(),
-                        message: "External script without SRI (Subresource Integrity) hash".to_string(),
+                        severity: Severity::Critical,
+                        file: file.to_string(),
+                        message: format!(
+                            "{} integrity hash for '{}' does not match the fetched bytes - possible supply-chain tampering",
+                            strongest.algorithm, url
+                        ),
                         owasp: Some("A08:2021"),
+                        line: None,
                     });
                 }
+            }
+        }
+    }
+}
 
-                // Check for external stylesheets without SRI
-                let link_re = Regex::new(r#"<link[^>]+href\s*=\s*["']https?://"#).unwrap();
-                if link_re.is_match(&content) && !integrity_re.is_match(&content) {
-                    issues.push(SecurityIssue {
-                        severity: Severity::Low,
-                        file: file.clone(),
-                        message: "External stylesheet without SRI hash".to_string(),
-                        owasp: Some("A08:2021"),
-                    });
-                }
+fn check_software_integrity(files: &[String], diff: &str, issues: &mut Vec<SecurityIssue>, verify_sri: bool) {
+    for file in files {
+        if file.ends_with(".tsx") || file.ends_with(".jsx") || file.ends_with(".html") {
+            if let Ok(content) = fs::read_to_string(file) {
+                check_tag_integrity(&content, "script", "src", Severity::Medium, file, issues, verify_sri);
+                check_tag_integrity(&content, "link", "href", Severity::Low, file, issues, verify_sri);
             }
         }
 
@@ -1283,6 +2064,7 @@ fn check_software_integrity(files: &[String], diff: &str, issues: &mut Vec<Secur
                         file: file.clone(),
                         message: "Wildcard in remotePatterns - restrict to specific domains".to_string(),
                         owasp: Some("A08:2021"),
+                        line: None,
                     });
                 }
             }
@@ -1290,21 +2072,153 @@ fn check_software_integrity(files: &[String], diff: &str, issues: &mut Vec<Secur
     }
 
     // Check diff for new CDN dependencies
-    for line in diff.lines().filter(|l| l.starts_with('+')) {
-        if line.contains("cdn.") || line.contains("unpkg.com") || line.contains("jsdelivr") {
-            if !line.contains("integrity") {
+    if let Some(hit) = parse_diff_added_lines(diff).iter().find(|l| {
+        (l.content.contains("cdn.") || l.content.contains("unpkg.com") || l.content.contains("jsdelivr"))
+            && !l.content.contains("integrity")
+    }) {
+        issues.push(SecurityIssue {
+            severity: Severity::Medium,
+            file: hit.file.clone(),
+            message: "CDN resource added without integrity hash".to_string(),
+            owasp: Some("A08:2021"),
+            line: Some(hit.line),
+        });
+    }
+}
+
+// =============================================================================
+// RESPONSE HEADER HARDENING - next.config `headers()` + middleware mutations
+// =============================================================================
+/// A server normally attaches its hardening headers globally on every
+/// response; in this app that's either `next.config`'s `async headers()` or
+/// a `middleware.ts` response mutation. Complements the SRI checks above -
+/// SRI pins what a resource's bytes must be, this enforces the headers that
+/// make that pin (and the rest of the browser's security model) effective.
+fn check_response_header_hardening(files: &[String], issues: &mut Vec<SecurityIssue>) {
+    let baseline_headers = [
+        ("Content-Security-Policy", "Content-Security-Policy header not set globally"),
+        ("Strict-Transport-Security", "Strict-Transport-Security header not set globally"),
+        ("X-Content-Type-Options", "X-Content-Type-Options: nosniff header not set globally"),
+        ("Referrer-Policy", "Referrer-Policy header not set globally"),
+        ("Permissions-Policy", "Permissions-Policy header not set globally"),
+    ];
+
+    for file in files {
+        let is_next_config = file.contains("next.config");
+        let is_middleware = file.contains("middleware.ts") || file.contains("middleware.js");
+        if !is_next_config && !is_middleware {
+            continue;
+        }
+
+        let Ok(content) = fs::read_to_string(file) else {
+            continue;
+        };
+
+        let declares_headers = content.contains("headers()") || content.contains("NextResponse.next()") || content.contains("response.headers.set");
+        if !declares_headers {
+            continue;
+        }
+
+        for (header, message) in baseline_headers {
+            if !content.contains(header) {
                 issues.push(SecurityIssue {
                     severity: Severity::Medium,
-                    file: "diff".to_string(),
-                    message: "CDN resource added without integrity hash".to_string(),
-                    owasp: Some("A08:2021"),
+                    file: file.clone(),
+                    message: message.to_string(),
+                    owasp: Some("A05:2021"),
+                    line: None,
+                });
+            }
+        }
+
+        // Frame protection: either CSP frame-ancestors or X-Frame-Options
+        if !content.contains("frame-ancestors") && !content.contains("X-Frame-Options") {
+            issues.push(SecurityIssue {
+                severity: Severity::Medium,
+                file: file.clone(),
+                message: "No frame protection set - add CSP frame-ancestors or X-Frame-Options".to_string(),
+                owasp: Some("A05:2021"),
+                line: None,
+            });
+        }
+
+        // HSTS should carry a real max-age and includeSubDomains, not just
+        // the bare header name
+        if content.contains("Strict-Transport-Security") {
+            let hsts_re = Regex::new(r"max-age=(\d+)").unwrap();
+            match hsts_re.captures(&content).and_then(|c| c[1].parse::<u64>().ok()) {
+                None => issues.push(SecurityIssue {
+                    severity: Severity::Medium,
+                    file: file.clone(),
+                    message: "Strict-Transport-Security header has no max-age".to_string(),
+                    owasp: Some("A05:2021"),
+                    line: None,
+                }),
+                Some(max_age) if max_age < 15_552_000 => issues.push(SecurityIssue {
+                    severity: Severity::Low,
+                    file: file.clone(),
+                    message: format!("Strict-Transport-Security max-age={} is below the recommended 180 days", max_age),
+                    owasp: Some("A05:2021"),
+                    line: None,
+                }),
+                _ => {}
+            }
+
+            if !content.contains("includeSubDomains") {
+                issues.push(SecurityIssue {
+                    severity: Severity::Low,
+                    file: file.clone(),
+                    message: "Strict-Transport-Security header missing includeSubDomains".to_string(),
+                    owasp: Some("A05:2021"),
+                    line: None,
                 });
-                break;
             }
         }
+
+        check_csp_weak_sources(&content, file, issues);
     }
 }
 
+/// Pulls the `Content-Security-Policy` value out of whichever quoted
+/// string/template literal holds it and flags directives that defeat the
+/// policy's own purpose: `unsafe-inline`/`unsafe-eval`, and a `*`/`https:`
+/// wildcard source list that allows loading from anywhere.
+fn check_csp_weak_sources(content: &str, file: &str, issues: &mut Vec<SecurityIssue>) {
+    let csp_re = Regex::new(r#"Content-Security-Policy['"]?\s*[:,]\s*[`'"]([^`'"]+)[`'"]"#).unwrap();
+    let Some(csp) = csp_re.captures(content).map(|c| c[1].to_string()) else {
+        return;
+    };
+
+    if csp.contains("unsafe-inline") {
+        issues.push(SecurityIssue {
+            severity: Severity::Medium,
+            file: file.to_string(),
+            message: "Content-Security-Policy allows 'unsafe-inline' - defeats CSP's XSS protection".to_string(),
+            owasp: Some("A05:2021"),
+            line: None,
+        });
+    }
+
+    if csp.contains("unsafe-eval") {
+        issues.push(SecurityIssue {
+            severity: Severity::Medium,
+            file: file.to_string(),
+            message: "Content-Security-Policy allows 'unsafe-eval' - defeats CSP's code-injection protection".to_string(),
+            owasp: Some("A05:2021"),
+            line: None,
+        });
+    }
+
+    if Regex::new(r"(?:^|\s)(\*|https:)(?:\s|$)").unwrap().is_match(&csp) {
+        issues.push(SecurityIssue {
+            severity: Severity::Medium,
+            file: file.to_string(),
+            message: "Content-Security-Policy source list has a '*'/'https:' wildcard - restrict to specific origins".to_string(),
+            owasp: Some("A05:2021"),
+            line: None,
+        });
+    }
+}
 
 // =============================================================================
 // OWASP A09:2021 - Security Logging and Monitoring
@@ -1331,6 +2245,7 @@ fn check_security_logging(files: &[String], issues: &mut Vec<SecurityIssue>) {
                             file: file.clone(),
                             message: "Auth-related code without security logging".to_string(),
                             owasp: Some("A09:2021"),
+                            line: None,
                         });
                     }
                 }
@@ -1343,6 +2258,7 @@ fn check_security_logging(files: &[String], issues: &mut Vec<SecurityIssue>) {
                             file: file.clone(),
                             message: "Error catch block without logging - security events may be missed".to_string(),
                             owasp: Some("A09:2021"),
+                            line: None,
                         });
                     }
                 }
@@ -1377,6 +2293,7 @@ fn check_csrf_protection(files: &[String], issues: &mut Vec<SecurityIssue>) {
                         file: file.clone(),
                         message: "API route handles mutations without CSRF protection".to_string(),
                         owasp: Some("A01:2021"),
+                        line: None,
                     });
                 }
             }
@@ -1394,6 +2311,7 @@ fn check_csrf_protection(files: &[String], issues: &mut Vec<SecurityIssue>) {
                             file: file.clone(),
                             message: "Form POST without Server Action or CSRF token".to_string(),
                             owasp: Some("A01:2021"),
+                            line: None,
                         });
                     }
                 }
@@ -1429,6 +2347,7 @@ fn check_input_validation(files: &[String], issues: &mut Vec<SecurityIssue>) {
                         file: file.clone(),
                         message: "User input without schema validation (use zod/yup)".to_string(),
                         owasp: Some("A03:2021"),
+                        line: None,
                     });
                 }
 
@@ -1440,6 +2359,7 @@ fn check_input_validation(files: &[String], issues: &mut Vec<SecurityIssue>) {
                             file: file.clone(),
                             message: "Type assertion without validation - use schema validation".to_string(),
                             owasp: Some("A03:2021"),
+                            line: None,
                         });
                     }
                 }
@@ -1474,6 +2394,7 @@ fn check_jwt_security(files: &[String], diff: &str, issues: &mut Vec<SecurityIss
                                 file: file.clone(),
                                 message: msg.to_string(),
                                 owasp: Some("A02:2021"),
+                                line: None,
                             });
                         }
                     }
@@ -1486,6 +2407,7 @@ fn check_jwt_security(files: &[String], diff: &str, issues: &mut Vec<SecurityIss
                         file: file.clone(),
                         message: "JWT in URL query parameter - use Authorization header".to_string(),
                         owasp: Some("A02:2021"),
+                        line: None,
                     });
                 }
 
@@ -1496,26 +2418,182 @@ fn check_jwt_security(files: &[String], diff: &str, issues: &mut Vec<SecurityIss
                         file: file.clone(),
                         message: "JWT created without expiration - tokens should expire".to_string(),
                         owasp: Some("A02:2021"),
+                        line: None,
                     });
                 }
+
+                check_jwt_verification_calls(&content, file, issues);
             }
+
+            // Decode any literal JWT found in the file and inspect its
+            // actual header/claims, regardless of whether the surrounding
+            // text happens to mention "jwt".
+            scan_decoded_jwt_claims(&content, file, issues);
         }
     }
 
     // Check diff for JWT issues
-    for line in diff.lines().filter(|l| l.starts_with('+')) {
-        if line.to_lowercase().contains("jwt") && line.contains("localStorage") {
+    if let Some(hit) = parse_diff_added_lines(diff)
+        .iter()
+        .find(|l| l.content.to_lowercase().contains("jwt") && l.content.contains("localStorage"))
+    {
+        issues.push(SecurityIssue {
+            severity: Severity::High,
+            file: hit.file.clone(),
+            message: "JWT stored in localStorage - use httpOnly cookies".to_string(),
+            owasp: Some("A02:2021"),
+            line: Some(hit.line),
+        });
+    }
+}
+
+/// Decodes every literal JWT in `content` and inspects its actual header
+/// and claims, rather than inferring from surrounding text. A JWT carries
+/// an app identity plus access scope, so a decoded `service_role` claim -
+/// this being a Supabase codebase - is as serious a secret leak as a raw
+/// API key, since it bypasses every RLS policy.
+fn scan_decoded_jwt_claims(content: &str, file: &str, issues: &mut Vec<SecurityIssue>) {
+    let config = FoodcheckConfig::load(Path::new(".")).unwrap_or_default();
+    let max_lifetime_secs = config.globs_for("jwt").max_jwt_lifetime_days.unwrap_or(30) * 24 * 3600;
+
+    for token in jwt_inspect::find_candidates(content) {
+        let Some(decoded) = jwt_inspect::decode(token) else {
+            continue;
+        };
+
+        let alg = decoded.header.get("alg").and_then(|a| a.as_str()).unwrap_or("");
+        if alg.eq_ignore_ascii_case("none") {
+            issues.push(SecurityIssue {
+                severity: Severity::Critical,
+                file: file.to_string(),
+                message: "Decoded JWT header declares alg: \"none\" - the token requires no signature at all".to_string(),
+                owasp: Some("A02:2021"),
+                line: None,
+            });
+        } else if alg.starts_with("HS") && (content.contains("publicKey") || content.contains("RS256") || content.contains("ES256")) {
             issues.push(SecurityIssue {
                 severity: Severity::High,
-                file: "diff".to_string(),
-                message: "JWT stored in localStorage - use httpOnly cookies".to_string(),
+                file: file.to_string(),
+                message: format!("Decoded JWT header uses symmetric {} alongside an asymmetric key elsewhere in this file - possible key-confusion token", alg),
+                owasp: Some("A02:2021"),
+                line: None,
+            });
+        }
+
+        match (decoded.payload.get("exp").and_then(|v| v.as_i64()), decoded.payload.get("iat").and_then(|v| v.as_i64())) {
+            (None, _) => {
+                issues.push(SecurityIssue {
+                    severity: Severity::High,
+                    file: file.to_string(),
+                    message: "Decoded JWT payload has no exp claim - the token never expires".to_string(),
+                    owasp: Some("A02:2021"),
+                    line: None,
+                });
+            }
+            (Some(exp), Some(iat)) if exp - iat > max_lifetime_secs => {
+                issues.push(SecurityIssue {
+                    severity: Severity::Medium,
+                    file: file.to_string(),
+                    message: format!("Decoded JWT lifetime of {} days exceeds the configured {}-day maximum", (exp - iat) / 86400, max_lifetime_secs / 86400),
+                    owasp: Some("A02:2021"),
+                    line: None,
+                });
+            }
+            _ => {}
+        }
+
+        if decoded.payload.get("role").and_then(|r| r.as_str()) == Some("service_role") {
+            issues.push(SecurityIssue {
+                severity: Severity::Critical,
+                file: file.to_string(),
+                message: "Decoded JWT payload has role: \"service_role\" - a committed Supabase service-role key bypasses all RLS policies".to_string(),
                 owasp: Some("A02:2021"),
+                line: None,
             });
-            break;
         }
     }
 }
 
+/// Catches the signature-verification bugs that keyword matching above
+/// misses: for each `jwt.verify(`/`jwtVerify(` call, pulls the balanced
+/// argument text and checks whether it actually pins an `algorithms`
+/// allowlist, rather than just looking for the word "algorithm" anywhere
+/// in the file.
+fn check_jwt_verification_calls(content: &str, file: &str, issues: &mut Vec<SecurityIssue>) {
+    let algorithms_option = Regex::new(r#"(?i)algorithms?\s*[:=]"#).unwrap();
+    let uses_asymmetric_keys = content.contains("privateKey") || content.contains("publicKey");
+
+    for marker in ["jwt.verify(", "jwtVerify("] {
+        for (idx, _) in content.match_indices(marker) {
+            let call = extract_balanced_call(content, idx + marker.len());
+            let allows_none = call.to_lowercase().contains("\"none\"") || call.to_lowercase().contains("'none'");
+
+            if allows_none || !algorithms_option.is_match(call) {
+                issues.push(SecurityIssue {
+                    severity: Severity::Critical,
+                    file: file.to_string(),
+                    message: format!(
+                        "{}...) has no `algorithms` allowlist (or allows \"none\") - accepts unsigned/downgraded tokens",
+                        marker
+                    ),
+                    owasp: Some("A02:2021"),
+                    line: None,
+                });
+                continue;
+            }
+
+            if call.contains("HS256") && uses_asymmetric_keys {
+                issues.push(SecurityIssue {
+                    severity: Severity::Critical,
+                    file: file.to_string(),
+                    message: format!(
+                        "{}...) allows HS256 alongside RS256/ES256 key usage - a public key can be replayed as an HMAC secret (key confusion)",
+                        marker
+                    ),
+                    owasp: Some("A02:2021"),
+                    line: None,
+                });
+            }
+        }
+    }
+
+    if content.contains("jwt.decode(")
+        && !content.contains("jwt.verify(")
+        && !content.contains("jwtVerify(")
+    {
+        issues.push(SecurityIssue {
+            severity: Severity::High,
+            file: file.to_string(),
+            message: "jwt.decode() trusts claims without checking the signature - use jwt.verify() before reading them".to_string(),
+            owasp: Some("A02:2021"),
+            line: None,
+        });
+    }
+}
+
+/// Returns the text between a call's opening `(` (already consumed, `after`
+/// points just past it) and its matching close, tracking paren depth so
+/// nested object literals/arrays in the call don't truncate it early.
+fn extract_balanced_call(content: &str, after: usize) -> &str {
+    let bytes = content.as_bytes();
+    let mut depth: i32 = 1;
+    let mut i = after;
+    while i < bytes.len() {
+        match bytes[i] {
+            b'(' => depth += 1,
+            b')' => {
+                depth -= 1;
+                if depth == 0 {
+                    return &content[after..i];
+                }
+            }
+            _ => {}
+        }
+        i += 1;
+    }
+    &content[after..]
+}
+
 
 // =============================================================================
 // REDOS (Regex Denial of Service)
@@ -1541,6 +2619,7 @@ fn check_redos_patterns(files: &[String], diff: &str, issues: &mut Vec<SecurityI
                             file: file.clone(),
                             message: msg.to_string(),
                             owasp: Some("A03:2021"),
+                            line: None,
                         });
                     }
                 }
@@ -1553,6 +2632,7 @@ fn check_redos_patterns(files: &[String], diff: &str, issues: &mut Vec<SecurityI
                         file: file.clone(),
                         message: "User input in RegExp constructor - sanitize or use literal".to_string(),
                         owasp: Some("A03:2021"),
+                        line: None,
                     });
                 }
             }
@@ -1560,19 +2640,15 @@ fn check_redos_patterns(files: &[String], diff: &str, issues: &mut Vec<SecurityI
     }
 
     // Check diff for new regex patterns
-    for line in diff.lines().filter(|l| l.starts_with('+')) {
-        if line.contains("new RegExp") {
-            for (pattern, _) in &redos_patterns {
-                if line.contains(pattern) {
-                    issues.push(SecurityIssue {
-                        severity: Severity::Medium,
-                        file: "diff".to_string(),
-                        message: "Potentially vulnerable regex pattern added".to_string(),
-                        owasp: Some("A03:2021"),
-                    });
-                    break;
-                }
-            }
+    for hit in parse_diff_added_lines(diff).iter().filter(|l| l.content.contains("new RegExp")) {
+        if redos_patterns.iter().any(|(pattern, _)| hit.content.contains(pattern)) {
+            issues.push(SecurityIssue {
+                severity: Severity::Medium,
+                file: hit.file.clone(),
+                message: "Potentially vulnerable regex pattern added".to_string(),
+                owasp: Some("A03:2021"),
+                line: Some(hit.line),
+            });
         }
     }
 }
@@ -1601,6 +2677,7 @@ fn check_timing_attacks(files: &[String], diff: &str, issues: &mut Vec<SecurityI
                                 file: file.clone(),
                                 message: msg.to_string(),
                                 owasp: Some("A02:2021"),
+                                line: None,
                             });
                         }
                     }
@@ -1615,6 +2692,7 @@ fn check_timing_attacks(files: &[String], diff: &str, issues: &mut Vec<SecurityI
                         file: file.clone(),
                         message: "Password comparison without bcrypt/argon2 - use proper hashing".to_string(),
                         owasp: Some("A02:2021"),
+                        line: None,
                     });
                 }
             }
@@ -1622,28 +2700,292 @@ fn check_timing_attacks(files: &[String], diff: &str, issues: &mut Vec<SecurityI
     }
 
     // Check diff for timing-vulnerable patterns
-    for line in diff.lines().filter(|l| l.starts_with('+')) {
-        let line_lower = line.to_lowercase();
-        if (line_lower.contains("password") || line_lower.contains("secret") || line_lower.contains("token"))
-            && (line.contains("===") || line.contains("=="))
-            && !line.contains("timingSafeEqual")
-        {
+    if let Some(hit) = parse_diff_added_lines(diff).iter().find(|l| {
+        let content_lower = l.content.to_lowercase();
+        (content_lower.contains("password") || content_lower.contains("secret") || content_lower.contains("token"))
+            && (l.content.contains("===") || l.content.contains("=="))
+            && !l.content.contains("timingSafeEqual")
+    }) {
+        issues.push(SecurityIssue {
+            severity: Severity::Medium,
+            file: hit.file.clone(),
+            message: "Secret comparison added - consider constant-time comparison".to_string(),
+            owasp: Some("A02:2021"),
+            line: Some(hit.line),
+        });
+    }
+}
+
+/// Auditing *presence* of bcrypt/argon2 (`check_timing_attacks` above) says
+/// nothing about whether it's configured strongly - a cost factor of 4 or a
+/// PBKDF2 iteration count of 1000 defeats the point of using a slow hash at
+/// all. This parses the work-factor argument out of common call shapes and
+/// flags ones below the recommended minimum.
+fn check_password_hashing_strength(files: &[String], issues: &mut Vec<SecurityIssue>) {
+    let bcrypt_re = Regex::new(r"bcrypt\.?\w*\.hash\w*\(\s*[^,()]+,\s*(\d+)").unwrap();
+    let pbkdf2_re = Regex::new(r"pbkdf2\w*\(\s*[^,()]+,\s*[^,()]+,\s*(\d+)").unwrap();
+    let argon2_memory_re = Regex::new(r"memoryCost\s*:\s*(\d+)").unwrap();
+    let argon2_time_re = Regex::new(r"timeCost\s*:\s*(\d+)").unwrap();
+
+    const MIN_BCRYPT_COST: u32 = 12;
+    const MIN_PBKDF2_ITERATIONS: u32 = 100_000;
+    const MIN_ARGON2_MEMORY_KIB: u32 = 19 * 1024;
+    const MIN_ARGON2_TIME_COST: u32 = 2;
+
+    for file in files {
+        let Ok(content) = fs::read_to_string(file) else { continue };
+
+        for cap in bcrypt_re.captures_iter(&content) {
+            if let Ok(cost) = cap[1].parse::<u32>() {
+                if cost < MIN_BCRYPT_COST {
+                    issues.push(SecurityIssue {
+                        severity: Severity::High,
+                        file: file.clone(),
+                        message: format!(
+                            "bcrypt cost factor {} is below the recommended minimum of {}",
+                            cost, MIN_BCRYPT_COST
+                        ),
+                        owasp: Some("A02:2021"),
+                        line: None,
+                    });
+                }
+            }
+        }
+
+        for cap in pbkdf2_re.captures_iter(&content) {
+            if let Ok(iterations) = cap[1].parse::<u32>() {
+                if iterations < MIN_PBKDF2_ITERATIONS {
+                    issues.push(SecurityIssue {
+                        severity: Severity::High,
+                        file: file.clone(),
+                        message: format!(
+                            "PBKDF2 iteration count {} is below the recommended minimum of {}",
+                            iterations, MIN_PBKDF2_ITERATIONS
+                        ),
+                        owasp: Some("A02:2021"),
+                        line: None,
+                    });
+                }
+            }
+        }
+
+        if content.contains("argon2") {
+            if let Some(cap) = argon2_memory_re.captures(&content) {
+                if let Ok(memory_kib) = cap[1].parse::<u32>() {
+                    if memory_kib < MIN_ARGON2_MEMORY_KIB {
+                        issues.push(SecurityIssue {
+                            severity: Severity::High,
+                            file: file.clone(),
+                            message: format!(
+                                "Argon2 memoryCost {} KiB is below the recommended minimum of {} KiB (19 MiB)",
+                                memory_kib, MIN_ARGON2_MEMORY_KIB
+                            ),
+                            owasp: Some("A02:2021"),
+                            line: None,
+                        });
+                    }
+                }
+            }
+
+            if let Some(cap) = argon2_time_re.captures(&content) {
+                if let Ok(time_cost) = cap[1].parse::<u32>() {
+                    if time_cost < MIN_ARGON2_TIME_COST {
+                        issues.push(SecurityIssue {
+                            severity: Severity::High,
+                            file: file.clone(),
+                            message: format!(
+                                "Argon2 timeCost {} is below the recommended minimum of {}",
+                                time_cost, MIN_ARGON2_TIME_COST
+                            ),
+                            owasp: Some("A02:2021"),
+                            line: None,
+                        });
+                    }
+                }
+            }
+        }
+    }
+}
+
+
+// =============================================================================
+// RUNTIME TLS/HEADER POSTURE
+// =============================================================================
+
+/// Known-weak `testssl.sh` finding ids mapped to a `(Severity, owasp,
+/// message)` triple. Only the highest-impact, unambiguous findings are
+/// covered here — anything else `testssl` reports is left to the human
+/// reading the full JSON report.
+const WEAK_TLS_FINDINGS: &[(&str, Severity, &str, &str)] = &[
+    ("SSLv2", Severity::Critical, "A02:2021", "SSLv2 is enabled - broken protocol, disable immediately"),
+    ("SSLv3", Severity::Critical, "A02:2021", "SSLv3 is enabled - vulnerable to POODLE, disable immediately"),
+    ("TLS1", Severity::High, "A02:2021", "TLS 1.0 is enabled - deprecated protocol, disable"),
+    ("TLS1_1", Severity::High, "A02:2021", "TLS 1.1 is enabled - deprecated protocol, disable"),
+    ("cert_expirationStatus", Severity::High, "A05:2021", "TLS certificate is expired or expiring soon"),
+    ("cert_chain_of_trust", Severity::High, "A05:2021", "TLS certificate chain of trust is broken"),
+    ("HSTS", Severity::Medium, "A05:2021", "Strict-Transport-Security header missing from the live response"),
+];
+
+/// Live-scans a deployed URL: TLS/cert posture via `testssl.sh --jsonfile`
+/// (skipped with a warning if it isn't installed, so the static checks
+/// remain the default, always-available path) plus a plain HTTPS HEAD
+/// request to assert the security headers we expect `next.config`/
+/// middleware to set actually reach the client.
+fn check_runtime_posture(url: &str, issues: &mut Vec<SecurityIssue>) {
+    check_response_headers(url, issues);
+
+    let report_path = std::env::temp_dir().join(format!("testssl-{}.json", std::process::id()));
+    let spawn = Command::new("testssl.sh")
+        .args(["--quiet", "--jsonfile"])
+        .arg(&report_path)
+        .arg(url)
+        .output();
+
+    let output = match spawn {
+        Ok(output) => output,
+        Err(_) => {
+            print_warning("testssl.sh not found on PATH - skipping live TLS posture scan");
+            return;
+        }
+    };
+
+    if !output.status.success() {
+        print_warning("testssl.sh exited with an error - skipping live TLS posture scan");
+        return;
+    }
+
+    let Ok(raw) = fs::read_to_string(&report_path) else {
+        print_warning("testssl.sh produced no report file - skipping live TLS posture scan");
+        return;
+    };
+    let _ = fs::remove_file(&report_path);
+
+    let Ok(findings) = serde_json::from_str::<Vec<serde_json::Value>>(&raw) else {
+        print_warning("could not parse testssl.sh JSON output - skipping live TLS posture scan");
+        return;
+    };
+
+    for finding in &findings {
+        let Some(id) = finding.get("id").and_then(|v| v.as_str()) else { continue };
+        let offered_or_weak = finding
+            .get("finding")
+            .and_then(|v| v.as_str())
+            .map(|f| !f.eq_ignore_ascii_case("not offered") && !f.eq_ignore_ascii_case("ok"))
+            .unwrap_or(true);
+        if !offered_or_weak {
+            continue;
+        }
+
+        if let Some((_, severity, owasp, message)) = WEAK_TLS_FINDINGS.iter().find(|(wid, ..)| *wid == id) {
             issues.push(SecurityIssue {
-                severity: Severity::Medium,
-                file: "diff".to_string(),
-                message: "Secret comparison added - consider constant-time comparison".to_string(),
-                owasp: Some("A02:2021"),
+                severity: *severity,
+                file: url.to_string(),
+                message: message.to_string(),
+                owasp: Some(owasp),
+                line: None,
             });
-            break;
         }
     }
 }
 
+/// Issues a HEAD request against `url` and flags missing security headers
+/// that source-only checks (`check_vercel_security`, `check_security_config`)
+/// can only infer from `next.config`/middleware, not confirm were actually
+/// sent by the deployed edge.
+fn check_response_headers(url: &str, issues: &mut Vec<SecurityIssue>) {
+    let response = match ureq::head(url).call() {
+        Ok(response) => response,
+        Err(e) => {
+            print_warning(&format!("could not reach {} for a header check: {}", url, e));
+            return;
+        }
+    };
+
+    let required_headers = [
+        ("strict-transport-security", Severity::High, "A05:2021", "Strict-Transport-Security header missing from the live response"),
+        ("content-security-policy", Severity::Medium, "A05:2021", "Content-Security-Policy header missing from the live response"),
+        ("x-content-type-options", Severity::Medium, "A05:2021", "X-Content-Type-Options header missing from the live response"),
+    ];
+
+    for (header, severity, owasp, message) in required_headers {
+        if response.header(header).is_none() {
+            issues.push(SecurityIssue {
+                severity,
+                file: url.to_string(),
+                message: message.to_string(),
+                owasp: Some(owasp),
+                line: None,
+            });
+        }
+    }
+}
 
 // =============================================================================
 // SUMMARY
 // =============================================================================
-fn print_summary(issues: &[SecurityIssue]) -> Result<()> {
+fn print_summary(issues: &[SecurityIssue], thresholds: GateThresholds) -> Result<()> {
+    // Drop issues an accepted `.securityignore.json` entry silences, and
+    // separately flag entries that no longer match anything (the finding
+    // was fixed, or the entry has a typo) or have passed their `expires`
+    // date, so accepted debt doesn't get suppressed forever by accident.
+    let baseline = security_baseline::load(Path::new(".securityignore.json")).unwrap_or_default();
+    let mut baseline_matched = vec![false; baseline.len()];
+    let issues: Vec<&SecurityIssue> = issues
+        .iter()
+        .filter(|issue| {
+            let fingerprint = security_baseline::fingerprint(
+                &rule_id(issue.owasp, &issue.message),
+                &issue.file,
+                &issue.message,
+            );
+            match baseline.iter().position(|e| e.fingerprint == fingerprint) {
+                Some(idx) => {
+                    baseline_matched[idx] = true;
+                    baseline[idx]
+                        .expires
+                        .as_deref()
+                        .map(security_baseline::is_expired)
+                        .unwrap_or(false)
+                }
+                None => true,
+            }
+        })
+        .collect();
+    let issues = issues.as_slice();
+
+    for (entry, matched) in baseline.iter().zip(baseline_matched.iter()) {
+        let reason = entry.reason.as_deref().map(|r| format!(" — {}", r)).unwrap_or_default();
+        if !matched {
+            print_warning(&format!(
+                "Stale .securityignore.json entry (no longer matches any finding): {}{}",
+                entry.fingerprint, reason
+            ));
+        } else if let Some(expires) = &entry.expires {
+            if security_baseline::is_expired(expires) {
+                print_warning(&format!(
+                    "Expired .securityignore.json suppression (expired {}): {}{}",
+                    expires, entry.fingerprint, reason
+                ));
+            }
+        }
+    }
+
+    for issue in issues {
+        report::push(Finding {
+            check: "nextjs_security".to_string(),
+            file: issue.file.clone(),
+            line: issue.line,
+            severity: match issue.severity {
+                Severity::Critical | Severity::High => report::Severity::Error,
+                Severity::Medium => report::Severity::Warning,
+                Severity::Low => report::Severity::Note,
+            },
+            rule: rule_id(issue.owasp, &issue.message),
+            message: issue.message.clone(),
+            owasp: issue.owasp.map(String::from),
+        });
+    }
+
     println!();
     print_header("Security Scan Summary");
 
@@ -1658,7 +3000,11 @@ fn print_summary(issues: &[SecurityIssue]) -> Result<()> {
         print_error(&format!("🚨 CRITICAL ({}):", critical));
         for issue in issues.iter().filter(|i| matches!(i.severity, Severity::Critical)) {
             let owasp = issue.owasp.map(|o| format!(" [{}]", o)).unwrap_or_default();
-            print_error(&format!("  {} - {}{}", issue.file, issue.message, owasp));
+            let location = match issue.line {
+                Some(line) => format!("{}:{}", issue.file, line),
+                None => issue.file.clone(),
+            };
+            print_error(&format!("  {} - {}{}", location, issue.message, owasp));
         }
     }
 
@@ -1667,7 +3013,11 @@ fn print_summary(issues: &[SecurityIssue]) -> Result<()> {
         print_error(&format!("⛔ HIGH ({}):", high));
         for issue in issues.iter().filter(|i| matches!(i.severity, Severity::High)) {
             let owasp = issue.owasp.map(|o| format!(" [{}]", o)).unwrap_or_default();
-            print_error(&format!("  {} - {}{}", issue.file, issue.message, owasp));
+            let location = match issue.line {
+                Some(line) => format!("{}:{}", issue.file, line),
+                None => issue.file.clone(),
+            };
+            print_error(&format!("  {} - {}{}", location, issue.message, owasp));
         }
     }
 
@@ -1676,7 +3026,11 @@ fn print_summary(issues: &[SecurityIssue]) -> Result<()> {
         print_warning(&format!("⚠️  MEDIUM ({}):", medium));
         for issue in issues.iter().filter(|i| matches!(i.severity, Severity::Medium)) {
             let owasp = issue.owasp.map(|o| format!(" [{}]", o)).unwrap_or_default();
-            print_warning(&format!("  {} - {}{}", issue.file, issue.message, owasp));
+            let location = match issue.line {
+                Some(line) => format!("{}:{}", issue.file, line),
+                None => issue.file.clone(),
+            };
+            print_warning(&format!("  {} - {}{}", location, issue.message, owasp));
         }
     }
 
@@ -1685,7 +3039,11 @@ fn print_summary(issues: &[SecurityIssue]) -> Result<()> {
         print_verbose(&format!("ℹ️  LOW ({}):", low));
         for issue in issues.iter().filter(|i| matches!(i.severity, Severity::Low)) {
             let owasp = issue.owasp.map(|o| format!(" [{}]", o)).unwrap_or_default();
-            print_verbose(&format!("  {} - {}{}", issue.file, issue.message, owasp));
+            let location = match issue.line {
+                Some(line) => format!("{}:{}", issue.file, line),
+                None => issue.file.clone(),
+            };
+            print_verbose(&format!("  {} - {}{}", location, issue.message, owasp));
         }
     }
 
@@ -1697,9 +3055,34 @@ fn print_summary(issues: &[SecurityIssue]) -> Result<()> {
     );
     println!("━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━");
 
-    if critical > 0 || high > 0 {
-        print_error("Security check FAILED - fix critical/high issues before committing");
-        Err(anyhow::anyhow!("Security vulnerabilities detected"))
+    let mut by_owasp: BTreeMap<&'static str, usize> = BTreeMap::new();
+    for issue in issues {
+        if let Some(owasp) = issue.owasp {
+            *by_owasp.entry(owasp).or_insert(0) += 1;
+        }
+    }
+
+    let exceeds = |count: usize, max: Option<usize>| max.is_some_and(|max| count > max);
+    let gate_failed = exceeds(critical, thresholds.max_critical)
+        || exceeds(high, thresholds.max_high)
+        || exceeds(medium, thresholds.max_medium)
+        || exceeds(low, thresholds.max_low);
+
+    println!(
+        "{}",
+        serde_json::json!({
+            "critical": critical,
+            "high": high,
+            "medium": medium,
+            "low": low,
+            "byOwasp": by_owasp,
+            "passed": !gate_failed,
+        })
+    );
+
+    if gate_failed {
+        print_error("Security check FAILED - findings exceed the configured --max-* thresholds");
+        Err(anyhow::anyhow!("Security vulnerabilities exceed configured thresholds"))
     } else if medium > 0 {
         print_warning("Security check passed with warnings - review medium issues");
         Ok(())
@@ -1711,3 +3094,29 @@ fn print_summary(issues: &[SecurityIssue]) -> Result<()> {
         Ok(())
     }
 }
+
+/// Derives a stable SARIF `ruleId` for an issue that otherwise only carries
+/// free-text `message` and an optional OWASP category: the OWASP id (when
+/// present) prefixed onto a slug of the message, so the same kind of finding
+/// maps to the same rule across runs.
+fn rule_id(owasp: Option<&'static str>, message: &str) -> String {
+    match owasp {
+        Some(owasp) => format!("{}-{}", slugify(owasp), slugify(message)),
+        None => slugify(message),
+    }
+}
+
+fn slugify(s: &str) -> String {
+    let mut slug = String::with_capacity(s.len());
+    let mut last_was_dash = false;
+    for c in s.chars() {
+        if c.is_ascii_alphanumeric() {
+            slug.push(c.to_ascii_lowercase());
+            last_was_dash = false;
+        } else if !last_was_dash {
+            slug.push('-');
+            last_was_dash = true;
+        }
+    }
+    slug.trim_matches('-').to_string()
+}
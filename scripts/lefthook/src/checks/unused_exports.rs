@@ -1,5 +1,8 @@
+use crate::coverage::{self, ScriptCoverage};
+use crate::source_map;
 use crate::utils::{print_header, print_info, print_success, print_warning};
 use anyhow::Result;
+use std::path::Path;
 use std::process::Command;
 
 pub fn run() -> Result<()> {
@@ -48,3 +51,74 @@ pub fn run() -> Result<()> {
     // Warning only, not blocking
     Ok(())
 }
+
+/// Complements `run`'s purely static `ts-prune` pass with a dynamic signal:
+/// functions V8 recorded zero hits for across the whole suite. Read
+/// alongside the `ts-prune` section above, a function appearing in *both*
+/// is "statically unreferenced and never run" (safe to delete); one that
+/// only shows up here is "referenced somewhere but never actually executed"
+/// (worth checking whether it's genuinely dead or just untested/dynamic).
+pub fn run_with_coverage(test_cmd: &[&str]) -> Result<()> {
+    run()?;
+
+    print_header("🪦 Coverage-Based Dead Code Check");
+    let scripts = coverage::collect(test_cmd)?;
+    let scripts = coverage::merge(scripts);
+    let dead = zero_count_functions(&scripts);
+
+    if dead.is_empty() {
+        print_success("No functions with zero execution count");
+        return Ok(());
+    }
+
+    print_warning(&format!(
+        "Found {} function(s) never executed during the suite:",
+        dead.len()
+    ));
+    for (file, line, name) in dead.iter().take(20) {
+        println!("    {}:{} {}", file, line, name);
+    }
+    println!();
+    print_info("Cross-reference with the ts-prune section above to tell dead code from dynamically-used code");
+
+    // Non-blocking: this is a candidate list, not a hard failure.
+    Ok(())
+}
+
+/// Functions whose every top-level range has `count == 0` — i.e. the
+/// function body was never entered at all during the run, as distinct from
+/// a function that ran but took an unexecuted branch. When the emitted
+/// script has a source map, the reported file/line is translated back to
+/// the authored TypeScript rather than the transpiled output V8 actually
+/// ran, so it points where a developer would go fix it.
+fn zero_count_functions(scripts: &[ScriptCoverage]) -> Vec<(String, u32, String)> {
+    let mut dead = Vec::new();
+    for script in scripts {
+        let map = source_map::load_for(Path::new(&script.url));
+
+        for function in &script.functions {
+            let Some(top) = function.ranges.first() else { continue };
+            if top.count == 0 {
+                let generated_line = line_of(&script.source, top.start_offset);
+                let (file, line) = match &map {
+                    Some(map) => {
+                        let (original_file, original_line) = source_map::original_position(map, generated_line, 0);
+                        (original_file.unwrap_or_else(|| script.url.clone()), original_line)
+                    }
+                    None => (script.url.clone(), generated_line),
+                };
+                dead.push((file, line, function.function_name.clone()));
+            }
+        }
+    }
+    dead
+}
+
+fn line_of(source: &str, offset: u32) -> u32 {
+    source
+        .bytes()
+        .take(offset as usize)
+        .filter(|&b| b == b'\n')
+        .count() as u32
+        + 1
+}
@@ -0,0 +1,161 @@
+use super::ScriptCoverage;
+use anyhow::{bail, Context, Result};
+use serde_json::{json, Value};
+use std::process::{Child, Command, Stdio};
+use std::time::Duration;
+use tungstenite::{connect, Message};
+use url::Url;
+
+/// Port passed to `--inspect-brk`. Fixed rather than auto-picked so we know
+/// where to fetch the debugger's WebSocket URL from without scraping stderr.
+const INSPECTOR_PORT: u16 = 9229;
+
+/// A minimal synchronous CDP client: one WebSocket connection, one
+/// outstanding request at a time, matched by monotonically increasing `id`.
+/// That's all `collect` needs — we don't listen for unsolicited events.
+struct CdpClient {
+    socket: tungstenite::WebSocket<tungstenite::stream::MaybeTlsStream<std::net::TcpStream>>,
+    next_id: u64,
+}
+
+impl CdpClient {
+    fn connect(ws_url: &str) -> Result<Self> {
+        let url = Url::parse(ws_url).context("invalid inspector WebSocket URL")?;
+        let (socket, _) = connect(url).context("failed to connect to V8 inspector")?;
+        Ok(Self { socket, next_id: 1 })
+    }
+
+    fn call(&mut self, method: &str, params: Value) -> Result<Value> {
+        let id = self.next_id;
+        self.next_id += 1;
+
+        let request = json!({ "id": id, "method": method, "params": params });
+        self.socket.send(Message::Text(request.to_string()))?;
+
+        loop {
+            let msg = self.socket.read()?;
+            let Message::Text(text) = msg else { continue };
+            let reply: Value = serde_json::from_str(&text)?;
+            if reply.get("id").and_then(Value::as_u64) == Some(id) {
+                if let Some(error) = reply.get("error") {
+                    bail!("CDP call {} failed: {}", method, error);
+                }
+                return Ok(reply.get("result").cloned().unwrap_or(Value::Null));
+            }
+            // Any other message is an event (e.g. Debugger.scriptParsed) we
+            // don't need for collection; keep waiting for our reply.
+        }
+    }
+}
+
+/// Fetches the `webSocketDebuggerUrl` for the single target Node exposes at
+/// `http://127.0.0.1:<port>/json`.
+fn inspector_ws_url(port: u16) -> Result<String> {
+    let body = ureq::get(&format!("http://127.0.0.1:{}/json", port))
+        .call()
+        .context("could not reach the node --inspect-brk debugger endpoint")?
+        .into_string()?;
+    let targets: Vec<Value> = serde_json::from_str(&body)?;
+    let target = targets
+        .first()
+        .context("no inspector targets reported by node")?;
+    target["webSocketDebuggerUrl"]
+        .as_str()
+        .map(str::to_string)
+        .context("inspector target missing webSocketDebuggerUrl")
+}
+
+/// How long to wait for the test command to actually finish running before
+/// giving up on coverage collection - generous, since this is gating a full
+/// test suite run, not a single request.
+const RUN_TIMEOUT: Duration = Duration::from_secs(15 * 60);
+const POLL_INTERVAL: Duration = Duration::from_millis(100);
+
+/// Launches `test_cmd` under `node --inspect-brk=<port>`, waits for the
+/// inspector to come up, then drives it through the CDP `Profiler` domain to
+/// collect precise, per-function V8 coverage for every script it ran.
+pub fn collect(test_cmd: &[&str]) -> Result<Vec<ScriptCoverage>> {
+    let mut child = spawn_inspected(test_cmd)?;
+    let result = collect_from_running_inspector(INSPECTOR_PORT, &mut child);
+    let _ = child.wait();
+    result
+}
+
+fn spawn_inspected(test_cmd: &[&str]) -> Result<Child> {
+    let (program, args) = test_cmd.split_first().context("empty test command")?;
+    Command::new(program)
+        .arg(format!("--inspect-brk={}", INSPECTOR_PORT))
+        .args(args)
+        .stdout(Stdio::inherit())
+        .stderr(Stdio::inherit())
+        .spawn()
+        .context("failed to launch test command under node --inspect-brk")
+}
+
+fn collect_from_running_inspector(port: u16, child: &mut Child) -> Result<Vec<ScriptCoverage>> {
+    // The inspector needs a moment to open its HTTP/WS listener after spawn.
+    std::thread::sleep(Duration::from_millis(300));
+    let ws_url = inspector_ws_url(port)?;
+    let mut client = CdpClient::connect(&ws_url)?;
+
+    client.call("Debugger.enable", json!({}))?;
+    client.call("Profiler.enable", json!({}))?;
+    client.call(
+        "Profiler.startPreciseCoverage",
+        json!({ "callCount": true, "detailed": true }),
+    )?;
+
+    // Resume from the `--inspect-brk` breakpoint-on-start and let the suite
+    // actually run. Poll for the child to finish (bounded by RUN_TIMEOUT so a
+    // hung suite fails the hook instead of wedging it) before reading
+    // coverage back - taking it any earlier would only capture whatever ran
+    // in the first couple hundred milliseconds after resume.
+    client.call("Runtime.runIfWaitingForDebugger", json!({}))?;
+    wait_for_exit(child, RUN_TIMEOUT)?;
+
+    let coverage = client
+        .call("Profiler.takePreciseCoverage", json!({}))
+        .context("test command's inspector connection closed before coverage could be read")?;
+    let raw_scripts: Vec<Value> = serde_json::from_value(
+        coverage
+            .get("result")
+            .cloned()
+            .unwrap_or(Value::Array(vec![])),
+    )?;
+
+    let mut scripts = Vec::with_capacity(raw_scripts.len());
+    for raw in raw_scripts {
+        let mut script: ScriptCoverage = serde_json::from_value(raw.clone())?;
+        // Skip node internals (`node:...`) and scripts with no URL (eval'd
+        // snippets) — neither maps back to a file the suite is testing.
+        if script.url.is_empty() || script.url.starts_with("node:") {
+            continue;
+        }
+        let script_id = raw["scriptId"].as_str().unwrap_or_default().to_string();
+        if let Ok(source) = client.call(
+            "Debugger.getScriptSource",
+            json!({ "scriptId": script_id }),
+        ) {
+            script.source = source["scriptSource"].as_str().unwrap_or_default().to_string();
+        }
+        scripts.push(script);
+    }
+
+    Ok(scripts)
+}
+
+/// Polls `child` until it exits or `timeout` elapses, rather than blocking on
+/// a plain `child.wait()` forever - a suite that hangs should fail the hook
+/// with a clear error instead of leaving it stuck.
+fn wait_for_exit(child: &mut Child, timeout: Duration) -> Result<()> {
+    let start = std::time::Instant::now();
+    loop {
+        if child.try_wait()?.is_some() {
+            return Ok(());
+        }
+        if start.elapsed() > timeout {
+            bail!("test command did not finish within {:?}; giving up on coverage collection", timeout);
+        }
+        std::thread::sleep(POLL_INTERVAL);
+    }
+}
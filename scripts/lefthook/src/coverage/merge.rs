@@ -0,0 +1,200 @@
+use super::{CoverageRange, FunctionCoverage, ScriptCoverage};
+use std::collections::HashMap;
+
+/// A reconstructed range tree for one function: `[start, end)` holds
+/// `count`, and `children` are the strictly-nested sub-ranges V8 reports
+/// flattened (e.g. an `if` branch inside an executed function body).
+#[derive(Clone, Debug)]
+struct RangeTree {
+    start: u32,
+    end: u32,
+    count: u32,
+    children: Vec<RangeTree>,
+}
+
+/// Rebuilds the nesting a flat `ranges` list implies. V8 always emits the
+/// outermost range for a function first; sort ascending by `start_offset`
+/// and, on ties, descending by `end_offset` so a parent always precedes its
+/// children, then use a stack to attach each range to the last range still
+/// open (i.e. the top of stack whose `end` hasn't passed this range's
+/// `start`).
+fn build_tree(mut ranges: Vec<CoverageRange>) -> Option<RangeTree> {
+    if ranges.is_empty() {
+        return None;
+    }
+    ranges.sort_by(|a, b| {
+        a.start_offset
+            .cmp(&b.start_offset)
+            .then(b.end_offset.cmp(&a.end_offset))
+    });
+
+    let mut stack: Vec<RangeTree> = Vec::new();
+    for r in ranges {
+        let node = RangeTree {
+            start: r.start_offset,
+            end: r.end_offset,
+            count: r.count,
+            children: Vec::new(),
+        };
+
+        while let Some(top) = stack.last() {
+            if node.start >= top.end {
+                let done = stack.pop().unwrap();
+                attach(&mut stack, done);
+            } else {
+                break;
+            }
+        }
+        stack.push(node);
+    }
+
+    while stack.len() > 1 {
+        let done = stack.pop().unwrap();
+        attach(&mut stack, done);
+    }
+    stack.pop()
+}
+
+fn attach(stack: &mut [RangeTree], child: RangeTree) {
+    if let Some(parent) = stack.last_mut() {
+        parent.children.push(child);
+    }
+}
+
+/// All boundary offsets present anywhere in the tree, used to split both
+/// trees at every point either one distinguishes.
+fn collect_boundaries(tree: &RangeTree, out: &mut Vec<u32>) {
+    out.push(tree.start);
+    out.push(tree.end);
+    for child in &tree.children {
+        collect_boundaries(child, out);
+    }
+}
+
+/// The effective count at `offset` within `tree` — the innermost
+/// (most specific) node that contains it, falling back to 0 when `offset`
+/// falls outside the tree entirely (the "missing range contributes count 0"
+/// invariant).
+fn count_at(tree: Option<&RangeTree>, offset: u32) -> u32 {
+    let Some(tree) = tree else { return 0 };
+    if offset < tree.start || offset >= tree.end {
+        return 0;
+    }
+    for child in &tree.children {
+        if offset >= child.start && offset < child.end {
+            return count_at(Some(child), offset);
+        }
+    }
+    tree.count
+}
+
+/// Merges two (possibly absent) range trees for the same function into one
+/// flat, minimal list of `CoverageRange`s whose counts are the sum of both
+/// inputs at every point, coalescing adjacent segments with equal counts.
+fn merge_trees(a: Option<&RangeTree>, b: Option<&RangeTree>) -> Vec<CoverageRange> {
+    let mut boundaries = Vec::new();
+    if let Some(a) = a {
+        collect_boundaries(a, &mut boundaries);
+    }
+    if let Some(b) = b {
+        collect_boundaries(b, &mut boundaries);
+    }
+    boundaries.sort_unstable();
+    boundaries.dedup();
+
+    if boundaries.len() < 2 {
+        return Vec::new();
+    }
+
+    let mut merged: Vec<CoverageRange> = Vec::new();
+    for window in boundaries.windows(2) {
+        let (start, end) = (window[0], window[1]);
+        // Sample at `start` rather than the midpoint: both trees' intervals
+        // are half-open `[start, end)`, so `start` is always inside whatever
+        // segment this window represents.
+        let count = count_at(a, start) + count_at(b, start);
+
+        if let Some(last) = merged.last_mut() {
+            if last.end_offset == start && last.count == count {
+                last.end_offset = end;
+                continue;
+            }
+        }
+        merged.push(CoverageRange {
+            start_offset: start,
+            end_offset: end,
+            count,
+        });
+    }
+    merged
+}
+
+fn merge_function(a: Option<&FunctionCoverage>, b: Option<&FunctionCoverage>) -> FunctionCoverage {
+    let name = a
+        .map(|f| f.function_name.clone())
+        .or_else(|| b.map(|f| f.function_name.clone()))
+        .unwrap_or_default();
+    let is_block_coverage = a.map(|f| f.is_block_coverage).unwrap_or(false)
+        || b.map(|f| f.is_block_coverage).unwrap_or(false);
+
+    let tree_a = a.and_then(|f| build_tree(f.ranges.clone()));
+    let tree_b = b.and_then(|f| build_tree(f.ranges.clone()));
+
+    FunctionCoverage {
+        function_name: name,
+        ranges: merge_trees(tree_a.as_ref(), tree_b.as_ref()),
+        is_block_coverage,
+    }
+}
+
+/// Combines every `ScriptCoverage` for the same `url` (e.g. one per sharded
+/// test run) into a single record per script, summing hit counts over
+/// overlapping ranges rather than just concatenating or overwriting them.
+pub fn merge(runs: Vec<ScriptCoverage>) -> Vec<ScriptCoverage> {
+    let mut by_url: HashMap<String, Vec<ScriptCoverage>> = HashMap::new();
+    for script in runs {
+        by_url.entry(script.url.clone()).or_default().push(script);
+    }
+
+    by_url
+        .into_values()
+        .map(|mut scripts| {
+            let mut merged = scripts.remove(0);
+            for next in scripts {
+                merged = merge_two(merged, next);
+            }
+            merged
+        })
+        .collect()
+}
+
+/// Identifies a function within a script: `function_name` alone collides for
+/// distinct functions that share a name (most commonly `""`, V8's name for
+/// every anonymous/arrow function in the script), so pair it with the first
+/// range's start offset, which is unique per function.
+fn function_key(f: &FunctionCoverage) -> (String, u32) {
+    (f.function_name.clone(), f.ranges.first().map(|r| r.start_offset).unwrap_or(0))
+}
+
+fn merge_two(mut a: ScriptCoverage, b: ScriptCoverage) -> ScriptCoverage {
+    let mut functions_b: HashMap<(String, u32), FunctionCoverage> = b
+        .functions
+        .into_iter()
+        .map(|f| (function_key(&f), f))
+        .collect();
+
+    let mut merged_functions = Vec::new();
+    for fa in a.functions.drain(..) {
+        let fb = functions_b.remove(&function_key(&fa));
+        merged_functions.push(merge_function(Some(&fa), fb.as_ref()));
+    }
+    for (_, fb) in functions_b {
+        merged_functions.push(merge_function(None, Some(&fb)));
+    }
+
+    a.functions = merged_functions;
+    if a.source.is_empty() {
+        a.source = b.source;
+    }
+    a
+}
@@ -0,0 +1,57 @@
+//! Native V8 coverage collection, merging and reporting, used by
+//! `checks::test_coverage` instead of parsing a test-runner-specific
+//! `coverage-summary.json`.
+
+mod collect;
+mod merge;
+mod reporters;
+
+pub use collect::collect;
+pub use merge::merge;
+pub use reporters::{write_html, write_lcov, Reporter};
+
+use serde::{Deserialize, Serialize};
+
+/// One V8 range, as returned by `Profiler.takePreciseCoverage`. Offsets are
+/// byte offsets into the script's source text, never partially overlapping:
+/// two ranges are either disjoint or one strictly contains the other.
+#[derive(Deserialize, Serialize, Clone, Copy, Debug)]
+pub struct CoverageRange {
+    #[serde(rename = "startOffset")]
+    pub start_offset: u32,
+    #[serde(rename = "endOffset")]
+    pub end_offset: u32,
+    pub count: u32,
+}
+
+#[derive(Deserialize, Serialize, Clone, Debug)]
+pub struct FunctionCoverage {
+    #[serde(rename = "functionName")]
+    pub function_name: String,
+    pub ranges: Vec<CoverageRange>,
+    #[serde(rename = "isBlockCoverage")]
+    pub is_block_coverage: bool,
+}
+
+#[derive(Deserialize, Serialize, Clone, Debug)]
+pub struct ScriptCoverage {
+    #[serde(rename = "scriptId")]
+    pub script_id: String,
+    pub url: String,
+    pub functions: Vec<FunctionCoverage>,
+    /// The script's full source text, fetched via `Debugger.getScriptSource`
+    /// once coverage collection stops, needed to map offsets back to lines.
+    #[serde(default)]
+    pub source: String,
+}
+
+impl ScriptCoverage {
+    /// Whether any range of any function has a non-zero count, a quick "was
+    /// this script touched at all" signal.
+    pub fn is_executed(&self) -> bool {
+        self.functions
+            .iter()
+            .flat_map(|f| &f.ranges)
+            .any(|r| r.count > 0)
+    }
+}
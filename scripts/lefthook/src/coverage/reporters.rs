@@ -0,0 +1,192 @@
+use super::ScriptCoverage;
+use anyhow::{Context, Result};
+use std::fs;
+use std::path::Path;
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum Reporter {
+    Text,
+    Lcov,
+    Html,
+}
+
+impl Reporter {
+    pub fn parse(s: &str) -> Option<Self> {
+        match s {
+            "text" => Some(Reporter::Text),
+            "lcov" => Some(Reporter::Lcov),
+            "html" => Some(Reporter::Html),
+            _ => None,
+        }
+    }
+}
+
+struct FileLines {
+    /// 1-indexed line number -> hit count, for every executable line.
+    hits: Vec<(u32, u32)>,
+    function_hits: Vec<(String, u32, u32)>,
+}
+
+/// Buckets a script's function ranges into per-line hit counts, the shape
+/// both the LCOV and HTML writers need.
+fn file_lines(script: &ScriptCoverage) -> FileLines {
+    let mut line_starts = vec![0u32];
+    let mut offset = 0u32;
+    for b in script.source.bytes() {
+        offset += 1;
+        if b == b'\n' {
+            line_starts.push(offset);
+        }
+    }
+
+    let line_of = |o: u32| -> u32 {
+        match line_starts.binary_search(&o) {
+            Ok(i) => i as u32 + 1,
+            Err(i) => i as u32,
+        }
+    };
+
+    let mut hits: std::collections::BTreeMap<u32, u32> = std::collections::BTreeMap::new();
+    let mut function_hits = Vec::new();
+
+    for function in &script.functions {
+        if let Some(top) = function.ranges.first() {
+            function_hits.push((function.function_name.clone(), line_of(top.start_offset), top.count));
+        }
+        for range in &function.ranges {
+            let start_line = line_of(range.start_offset);
+            let end_line = line_of(range.end_offset.saturating_sub(1).max(range.start_offset));
+            for line in start_line..=end_line {
+                let entry = hits.entry(line).or_insert(0);
+                *entry = (*entry).max(range.count);
+            }
+        }
+    }
+
+    FileLines {
+        hits: hits.into_iter().collect(),
+        function_hits,
+    }
+}
+
+/// Writes an LCOV tracefile (`coverage/lcov.info`-shaped): one `SF`/`end_of_record`
+/// block per script, with `FN`/`FNDA` function records, `DA` per-line hit
+/// counts and the summary `LF/LH/FNF/FNH` totals LCOV consumers expect.
+/// Branch coverage isn't tracked by V8's `Profiler` domain at this
+/// granularity, so `BRF`/`BRH` are emitted as zero rather than guessed.
+pub fn write_lcov(scripts: &[ScriptCoverage], out_path: &Path) -> Result<()> {
+    let mut out = String::new();
+
+    for script in scripts {
+        if script.source.is_empty() {
+            continue;
+        }
+        let lines = file_lines(script);
+
+        out.push_str(&format!("SF:{}\n", script.url));
+        for (name, line, count) in &lines.function_hits {
+            out.push_str(&format!("FN:{},{}\n", line, name));
+            out.push_str(&format!("FNDA:{},{}\n", count, name));
+        }
+        let fnf = lines.function_hits.len();
+        let fnh = lines.function_hits.iter().filter(|(_, _, c)| *c > 0).count();
+        out.push_str(&format!("FNF:{}\n", fnf));
+        out.push_str(&format!("FNH:{}\n", fnh));
+
+        for (line, hits) in &lines.hits {
+            out.push_str(&format!("DA:{},{}\n", line, hits));
+        }
+        let lf = lines.hits.len();
+        let lh = lines.hits.iter().filter(|(_, h)| *h > 0).count();
+
+        out.push_str("BRF:0\n");
+        out.push_str("BRH:0\n");
+        out.push_str(&format!("LF:{}\n", lf));
+        out.push_str(&format!("LH:{}\n", lh));
+        out.push_str("end_of_record\n");
+    }
+
+    if let Some(parent) = out_path.parent() {
+        fs::create_dir_all(parent).context("creating coverage output directory")?;
+    }
+    fs::write(out_path, out).context("writing lcov.info")
+}
+
+/// Renders one HTML page per script with lines colored by hit count, plus
+/// an index listing each file's line-coverage percentage.
+pub fn write_html(scripts: &[ScriptCoverage], out_dir: &Path) -> Result<()> {
+    fs::create_dir_all(out_dir).context("creating coverage HTML output directory")?;
+
+    let mut index_rows = String::new();
+
+    for (i, script) in scripts.iter().enumerate() {
+        if script.source.is_empty() {
+            continue;
+        }
+        let lines = file_lines(script);
+        let hit_map: std::collections::HashMap<u32, u32> = lines.hits.into_iter().collect();
+
+        let mut body = String::new();
+        for (n, text) in script.source.lines().enumerate() {
+            let line_no = n as u32 + 1;
+            let (class, count_label) = match hit_map.get(&line_no) {
+                Some(0) => ("uncovered", "0".to_string()),
+                Some(h) => ("covered", h.to_string()),
+                None => ("neutral", "".to_string()),
+            };
+            body.push_str(&format!(
+                "<tr class=\"{}\"><td class=\"count\">{}</td><td class=\"line\">{}</td><td class=\"src\">{}</td></tr>\n",
+                class,
+                count_label,
+                line_no,
+                html_escape(text)
+            ));
+        }
+
+        let total = hit_map.len().max(1);
+        let covered = hit_map.values().filter(|h| **h > 0).count();
+        let pct = (covered as f64 / total as f64) * 100.0;
+
+        let page_name = format!("file-{}.html", i);
+        let page = format!(
+            "<!doctype html><html><head><meta charset=\"utf-8\"><title>{url}</title>\n\
+             <style>\n\
+             body {{ font-family: monospace; }}\n\
+             table {{ border-collapse: collapse; width: 100%; }}\n\
+             .count {{ width: 3em; text-align: right; color: #888; }}\n\
+             .line {{ width: 3em; text-align: right; color: #888; }}\n\
+             .covered {{ background: #e6ffed; }}\n\
+             .uncovered {{ background: #ffeef0; }}\n\
+             </style></head><body>\n\
+             <h1>{url}</h1><p>{pct:.1}% lines covered</p>\n\
+             <table>{body}</table>\n\
+             </body></html>",
+            url = html_escape(&script.url),
+            pct = pct,
+            body = body
+        );
+        fs::write(out_dir.join(&page_name), page)?;
+
+        index_rows.push_str(&format!(
+            "<tr><td><a href=\"{page}\">{url}</a></td><td>{pct:.1}%</td></tr>\n",
+            page = page_name,
+            url = html_escape(&script.url),
+            pct = pct
+        ));
+    }
+
+    let index = format!(
+        "<!doctype html><html><head><meta charset=\"utf-8\"><title>Coverage Report</title></head><body>\n\
+         <h1>Coverage Report</h1>\n\
+         <table><tr><th>File</th><th>Lines</th></tr>{}</table>\n\
+         </body></html>",
+        index_rows
+    );
+    fs::write(out_dir.join("index.html"), index).context("writing coverage HTML index")
+}
+
+fn html_escape(s: &str) -> String {
+    s.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+}
@@ -0,0 +1,39 @@
+//! Decodes JWTs found in source/diffs instead of just substring-matching
+//! the word "jwt": a three-segment base64url token's header and payload are
+//! plain JSON once decoded, so `check_jwt_security` can inspect `alg` and
+//! the actual claims rather than guessing from surrounding text.
+
+use base64::Engine;
+use regex::Regex;
+use serde_json::Value;
+
+pub struct DecodedJwt {
+    pub header: Value,
+    pub payload: Value,
+}
+
+/// Finds candidate JWTs: three dot-separated base64url segments, the first
+/// starting with `eyJ` (the base64 encoding of `{"`, true of every JSON JWT
+/// header) and each segment long enough to rule out incidental matches.
+pub fn find_candidates(content: &str) -> Vec<&str> {
+    let jwt_re = Regex::new(r"eyJ[A-Za-z0-9_-]{10,}\.[A-Za-z0-9_-]{10,}\.[A-Za-z0-9_-]{10,}").unwrap();
+    jwt_re.find_iter(content).map(|m| m.as_str()).collect()
+}
+
+/// Decodes a candidate's header and payload segments into JSON. Returns
+/// `None` for anything that isn't actually a JWT (e.g. some other
+/// base64url-looking blob that happened to match the shape above).
+pub fn decode(token: &str) -> Option<DecodedJwt> {
+    let mut parts = token.split('.');
+    let header_b64 = parts.next()?;
+    let payload_b64 = parts.next()?;
+
+    let header = decode_segment(header_b64)?;
+    let payload = decode_segment(payload_b64)?;
+    Some(DecodedJwt { header, payload })
+}
+
+fn decode_segment(segment: &str) -> Option<Value> {
+    let bytes = base64::engine::general_purpose::URL_SAFE_NO_PAD.decode(segment).ok()?;
+    serde_json::from_slice(&bytes).ok()
+}
@@ -0,0 +1,315 @@
+//! Version-aware supply-chain advisories for installed npm dependencies.
+//! Parses whichever lockfile is present into a `{name -> resolved version}`
+//! map and queries the [OSV](https://osv.dev) batch API, rather than the
+//! `nextjs_security` hardcoded "names we remembered" list this replaces.
+//! An offline cache (a saved OSV `querybatch` response) lets CI run
+//! deterministically without hitting the network on every commit.
+
+use anyhow::Result;
+use regex::Regex;
+use serde::Deserialize;
+use std::collections::BTreeSet;
+use std::fs;
+use std::path::Path;
+
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
+pub struct LockedPackage {
+    pub name: String,
+    pub version: String,
+}
+
+/// A single OSV advisory resolved against one locked package/version.
+pub struct Advisory {
+    pub package: String,
+    pub version: String,
+    pub id: String,
+    pub summary: String,
+    /// OSV's own severity label (e.g. `"CRITICAL"`), when it published one.
+    pub severity: Option<String>,
+}
+
+/// Reads whichever npm lockfile exists in `dir`, preferring
+/// `package-lock.json` since it's the most common and unambiguous to parse.
+/// Returns an empty, deduped set when no lockfile is present.
+pub fn locked_packages(dir: &Path) -> Vec<LockedPackage> {
+    let candidates: &[(&str, fn(&str) -> Vec<LockedPackage>)] = &[
+        ("package-lock.json", parse_package_lock),
+        ("pnpm-lock.yaml", parse_pnpm_lock),
+        ("yarn.lock", parse_yarn_lock),
+    ];
+
+    for (name, parser) in candidates {
+        let path = dir.join(name);
+        if let Ok(raw) = fs::read_to_string(&path) {
+            let mut packages: Vec<LockedPackage> = parser(&raw).into_iter().collect::<BTreeSet<_>>().into_iter().collect();
+            packages.dedup();
+            return packages;
+        }
+    }
+
+    Vec::new()
+}
+
+/// Parses npm's v2/v3 flat `packages` map (keyed by `node_modules/<name>`
+/// paths, with an empty-string key for the project root) as well as the
+/// older v1 recursive `dependencies` tree, since either shape can still show
+/// up depending on the npm version that last wrote the lockfile.
+fn parse_package_lock(raw: &str) -> Vec<LockedPackage> {
+    let Ok(doc) = serde_json::from_str::<serde_json::Value>(raw) else {
+        return Vec::new();
+    };
+
+    if let Some(packages) = doc.get("packages").and_then(|p| p.as_object()) {
+        return packages
+            .iter()
+            .filter_map(|(path, meta)| {
+                if path.is_empty() {
+                    return None;
+                }
+                let name = path.rsplit("node_modules/").next().unwrap_or(path);
+                let version = meta.get("version").and_then(|v| v.as_str())?;
+                Some(LockedPackage { name: name.to_string(), version: version.to_string() })
+            })
+            .collect();
+    }
+
+    let mut packages = Vec::new();
+    if let Some(deps) = doc.get("dependencies").and_then(|d| d.as_object()) {
+        collect_v1_dependencies(deps, &mut packages);
+    }
+    packages
+}
+
+fn collect_v1_dependencies(deps: &serde_json::Map<String, serde_json::Value>, out: &mut Vec<LockedPackage>) {
+    for (name, meta) in deps {
+        if let Some(version) = meta.get("version").and_then(|v| v.as_str()) {
+            out.push(LockedPackage { name: name.clone(), version: version.to_string() });
+        }
+        if let Some(nested) = meta.get("dependencies").and_then(|d| d.as_object()) {
+            collect_v1_dependencies(nested, out);
+        }
+    }
+}
+
+/// `pnpm-lock.yaml` is YAML, but nothing in this crate parses YAML, so this
+/// scans line-by-line for `packages:` section keys of the form
+/// `/name@version:` (or `/@scope/name@version:`) instead of pulling in a new
+/// dependency just for this one lockfile format.
+fn parse_pnpm_lock(raw: &str) -> Vec<LockedPackage> {
+    let key_line = Regex::new(r"^\s*/?(@?[^@/\s][^@]*)@([0-9][^(:\s]*)(?:\([^:]*\))?:?\s*$").unwrap();
+
+    raw.lines()
+        .filter_map(|line| {
+            let caps = key_line.captures(line)?;
+            Some(LockedPackage { name: caps[1].to_string(), version: caps[2].to_string() })
+        })
+        .collect()
+}
+
+/// `yarn.lock` isn't YAML either - each entry is a `name@range[, name@range]:`
+/// header line followed by an indented `version "x.y.z"` line.
+fn parse_yarn_lock(raw: &str) -> Vec<LockedPackage> {
+    let header = Regex::new(r#"^"?(@?[^@,"\s]+)@"#).unwrap();
+    let version_line = Regex::new(r#"^\s+version\s+"([^"]+)""#).unwrap();
+
+    let mut packages = Vec::new();
+    let mut pending_name: Option<String> = None;
+
+    for line in raw.lines() {
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        if !line.starts_with(' ') && line.ends_with(':') {
+            pending_name = header.captures(line).map(|c| c[1].to_string());
+            continue;
+        }
+        if let Some(caps) = version_line.captures(line) {
+            if let Some(name) = pending_name.take() {
+                packages.push(LockedPackage { name, version: caps[1].to_string() });
+            }
+        }
+    }
+
+    packages
+}
+
+#[derive(Deserialize)]
+struct BatchResponse {
+    #[serde(default)]
+    results: Vec<BatchResult>,
+}
+
+#[derive(Deserialize)]
+struct BatchResult {
+    #[serde(default)]
+    vulns: Vec<OsvVuln>,
+}
+
+#[derive(Deserialize)]
+struct OsvVuln {
+    id: String,
+    #[serde(default)]
+    summary: String,
+    #[serde(default)]
+    severity: Vec<OsvSeverityEntry>,
+    #[serde(default)]
+    database_specific: Option<serde_json::Value>,
+}
+
+#[derive(Deserialize)]
+struct OsvSeverityEntry {
+    #[serde(default)]
+    score: String,
+}
+
+/// Queries the OSV database for every locked package, or replays a cached
+/// `querybatch` response from `offline_cache` when given, so CI doesn't need
+/// network access (or non-determinism from the live feed) to gate a commit.
+pub fn query_advisories(packages: &[LockedPackage], offline_cache: Option<&Path>) -> Result<Vec<Advisory>> {
+    if packages.is_empty() {
+        return Ok(Vec::new());
+    }
+
+    let body = serde_json::json!({
+        "queries": packages.iter().map(|p| serde_json::json!({
+            "package": { "name": p.name, "ecosystem": "npm" },
+            "version": p.version,
+        })).collect::<Vec<_>>(),
+    });
+
+    let response: BatchResponse = if let Some(cache) = offline_cache.filter(|p| p.exists()) {
+        let raw = fs::read_to_string(cache)?;
+        serde_json::from_str(&raw)?
+    } else {
+        ureq::post("https://api.osv.dev/v1/querybatch")
+            .send_json(body)?
+            .into_json()?
+    };
+
+    let mut advisories = Vec::new();
+    for (package, result) in packages.iter().zip(response.results) {
+        for vuln in result.vulns {
+            advisories.push(Advisory {
+                package: package.name.clone(),
+                version: package.version.clone(),
+                id: vuln.id,
+                summary: vuln.summary,
+                severity: extract_severity(&vuln.severity, vuln.database_specific.as_ref()),
+            });
+        }
+    }
+
+    Ok(advisories)
+}
+
+/// Prefers OSV's own `database_specific.severity` label; falls back to a
+/// CVSS base score bucket computed from the vector OSV actually publishes in
+/// `severity[].score` (a full `CVSS:3.x/AV:.../...` vector string, not a bare
+/// number - naively `parse::<f64>()`-ing its segments never succeeds).
+fn extract_severity(severity: &[OsvSeverityEntry], database_specific: Option<&serde_json::Value>) -> Option<String> {
+    if let Some(label) = database_specific.and_then(|d| d.get("severity")).and_then(|s| s.as_str()) {
+        return Some(label.to_string());
+    }
+
+    severity.iter().find_map(|entry| cvss_v3_base_score(&entry.score).map(cvss_score_to_label))
+}
+
+/// Computes the CVSS v3.x base score from a vector string like
+/// `CVSS:3.1/AV:N/AC:L/PR:N/UI:N/S:U/C:H/I:H/A:H`, per the official
+/// first.org formula. Returns `None` for anything that isn't a CVSS v3
+/// vector (e.g. CVSS v2) or is missing a required metric.
+fn cvss_v3_base_score(vector: &str) -> Option<f64> {
+    if !vector.starts_with("CVSS:3") {
+        return None;
+    }
+
+    let metrics: std::collections::HashMap<&str, &str> = vector
+        .split('/')
+        .filter_map(|part| part.split_once(':'))
+        .collect();
+
+    let av = match *metrics.get("AV")? {
+        "N" => 0.85,
+        "A" => 0.62,
+        "L" => 0.55,
+        "P" => 0.2,
+        _ => return None,
+    };
+    let ac = match *metrics.get("AC")? {
+        "L" => 0.77,
+        "H" => 0.44,
+        _ => return None,
+    };
+    let scope_changed = match *metrics.get("S")? {
+        "U" => false,
+        "C" => true,
+        _ => return None,
+    };
+    let pr = match (*metrics.get("PR")?, scope_changed) {
+        ("N", _) => 0.85,
+        ("L", false) => 0.62,
+        ("L", true) => 0.68,
+        ("H", false) => 0.27,
+        ("H", true) => 0.5,
+        _ => return None,
+    };
+    let ui = match *metrics.get("UI")? {
+        "N" => 0.85,
+        "R" => 0.62,
+        _ => return None,
+    };
+    let cia = |m: &str| -> Option<f64> {
+        match m {
+            "N" => Some(0.0),
+            "L" => Some(0.22),
+            "H" => Some(0.56),
+            _ => None,
+        }
+    };
+    let c = cia(metrics.get("C")?)?;
+    let i = cia(metrics.get("I")?)?;
+    let a = cia(metrics.get("A")?)?;
+
+    let iss = 1.0 - (1.0 - c) * (1.0 - i) * (1.0 - a);
+    let impact = if scope_changed {
+        7.52 * (iss - 0.029) - 3.25 * (iss - 0.02).powf(15.0)
+    } else {
+        6.42 * iss
+    };
+    let exploitability = 8.22 * av * ac * pr * ui;
+
+    if impact <= 0.0 {
+        return Some(0.0);
+    }
+
+    let score = if scope_changed {
+        1.08 * (impact + exploitability)
+    } else {
+        impact + exploitability
+    };
+
+    Some(roundup(score.min(10.0)))
+}
+
+/// CVSS's own "round up to the nearest 0.1" rule, not standard rounding.
+fn roundup(score: f64) -> f64 {
+    let int_score = (score * 100_000.0).round() as i64;
+    if int_score % 10_000 == 0 {
+        int_score as f64 / 100_000.0
+    } else {
+        ((int_score / 10_000) + 1) as f64 / 10.0
+    }
+}
+
+fn cvss_score_to_label(score: f64) -> String {
+    if score >= 9.0 {
+        "CRITICAL"
+    } else if score >= 7.0 {
+        "HIGH"
+    } else if score >= 4.0 {
+        "MODERATE"
+    } else {
+        "LOW"
+    }
+    .to_string()
+}
@@ -0,0 +1,187 @@
+use once_cell::sync::Lazy;
+use serde::Serialize;
+use std::sync::Mutex;
+
+#[derive(Serialize, Clone, Copy, PartialEq, Eq)]
+pub enum Severity {
+    Error,
+    Warning,
+    Note,
+}
+
+impl Severity {
+    /// SARIF `level` values: https://docs.oasis-open.org/sarif/sarif/v2.1.0
+    fn sarif_level(self) -> &'static str {
+        match self {
+            Severity::Error => "error",
+            Severity::Warning => "warning",
+            Severity::Note => "note",
+        }
+    }
+}
+
+/// A single structured result, independent of the colored `print_*` output a
+/// check also prints for humans. Every finding-emitting check pushes one of
+/// these per issue via [`push`].
+#[derive(Serialize, Clone)]
+pub struct Finding {
+    pub check: String,
+    pub file: String,
+    pub line: Option<u32>,
+    pub severity: Severity,
+    pub rule: String,
+    pub message: String,
+    /// An OWASP Top 10 category tag (e.g. `"A03:2021"`), when the check that
+    /// produced this finding maps onto one. Surfaced as a SARIF rule
+    /// `properties.owasp` tag for dashboards that group by category.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub owasp: Option<String>,
+}
+
+static FINDINGS: Lazy<Mutex<Vec<Finding>>> = Lazy::new(|| Mutex::new(Vec::new()));
+
+pub fn push(finding: Finding) {
+    FINDINGS.lock().unwrap().push(finding);
+}
+
+/// Drains every finding collected so far (across however many checks ran in
+/// this process), so `main` can render one report - for a `pre-commit` run,
+/// one covering every sub-check - instead of each sub-check emitting its own.
+pub fn take_all() -> Vec<Finding> {
+    std::mem::take(&mut FINDINGS.lock().unwrap())
+}
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum Format {
+    Text,
+    Json,
+    Sarif,
+    Github,
+}
+
+impl Format {
+    pub fn parse(s: &str) -> Option<Self> {
+        match s {
+            "text" => Some(Format::Text),
+            "json" => Some(Format::Json),
+            "sarif" => Some(Format::Sarif),
+            "github" => Some(Format::Github),
+            _ => None,
+        }
+    }
+}
+
+/// Serializes the collected findings per `--format`. `Text` returns an empty
+/// string since the human-readable output is already printed inline by each
+/// check's `print_*` calls as it runs.
+pub fn render(findings: &[Finding], format: Format) -> String {
+    match format {
+        Format::Text => String::new(),
+        Format::Json => serde_json::to_string_pretty(findings).unwrap_or_default(),
+        Format::Sarif => render_sarif(findings),
+        Format::Github => render_github(findings),
+    }
+}
+
+/// Renders each finding as a GitHub Actions workflow command
+/// (`::error file=...,line=...::message`), so a CI job can `echo` these
+/// straight into pull request annotations without a separate reporting
+/// action. `Severity::Error` becomes `::error`, `Warning`/`Note` become
+/// `::warning` - there's no `::notice`-worthy distinction for a security
+/// finding.
+fn render_github(findings: &[Finding]) -> String {
+    findings
+        .iter()
+        .map(|f| {
+            let command = match f.severity {
+                Severity::Error => "error",
+                Severity::Warning | Severity::Note => "warning",
+            };
+            let mut properties = vec![format!("file={}", escape_workflow_property(&f.file))];
+            if let Some(line) = f.line {
+                properties.push(format!("line={}", line));
+            }
+            format!(
+                "::{} {}::{}",
+                command,
+                properties.join(","),
+                escape_workflow_message(&f.message)
+            )
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// Escapes a workflow command's free-text message per the Actions command
+/// grammar: https://docs.github.com/en/actions/using-workflows/workflow-commands-for-github-actions
+fn escape_workflow_message(s: &str) -> String {
+    s.replace('%', "%25").replace('\r', "%0D").replace('\n', "%0A")
+}
+
+/// Escapes a workflow command's `key=value` property value, which also
+/// needs `,` and `:` escaped since those delimit properties and the
+/// command name.
+fn escape_workflow_property(s: &str) -> String {
+    escape_workflow_message(s).replace(',', "%2C").replace(':', "%3A")
+}
+
+fn render_sarif(findings: &[Finding]) -> String {
+    // Keyed by rule id alone: two findings can share a rule id (same OWASP
+    // category + message template) from different checks, and the first
+    // one seen picks the rule's description and OWASP tag, so this just
+    // needs any one representative message.
+    let mut rules_by_id: std::collections::BTreeMap<&str, (Option<&str>, &str)> = std::collections::BTreeMap::new();
+    for f in findings {
+        rules_by_id.entry(&f.rule).or_insert((f.owasp.as_deref(), &f.message));
+    }
+
+    let rules: Vec<serde_json::Value> = rules_by_id
+        .iter()
+        .map(|(id, (owasp, message))| {
+            let mut rule = serde_json::json!({
+                "id": id,
+                "shortDescription": { "text": id },
+                "fullDescription": { "text": message },
+            });
+            if let Some(owasp) = owasp {
+                rule["properties"] = serde_json::json!({ "owasp": owasp });
+            }
+            rule
+        })
+        .collect();
+
+    let results: Vec<serde_json::Value> = findings
+        .iter()
+        .map(|f| {
+            serde_json::json!({
+                "ruleId": f.rule,
+                "level": f.severity.sarif_level(),
+                "message": { "text": f.message },
+                "locations": [{
+                    "physicalLocation": {
+                        "artifactLocation": { "uri": f.file },
+                        "region": { "startLine": f.line.unwrap_or(1) },
+                    }
+                }],
+            })
+        })
+        .collect();
+
+    let doc = serde_json::json!({
+        "version": "2.1.0",
+        "$schema": "https://raw.githubusercontent.com/oasis-tcs/sarif-spec/master/Schemata/sarif-schema-2.1.0.json",
+        "runs": [{
+            "tool": {
+                "driver": {
+                    "name": "lefthook-rs",
+                    "fullName": "FoodShare lefthook security scanner",
+                    "shortDescription": { "text": "OWASP Top 10 / Next.js / React / Vercel security checks for FoodShare" },
+                    "rules": rules,
+                }
+            },
+            "results": results,
+        }],
+    });
+
+    serde_json::to_string_pretty(&doc).unwrap_or_default()
+}
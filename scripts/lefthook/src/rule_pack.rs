@@ -0,0 +1,150 @@
+//! External rule packs for `nextjs_security`: `.foodrules.json` /
+//! `.foodrules.yaml` let a project ship its own regex/substring checks (or
+//! silence a built-in false positive via a narrower replacement) without
+//! patching the binary, merged in alongside the compiled-in `*_patterns`
+//! arrays at scan time.
+
+use anyhow::{Context, Result};
+use globset::Glob;
+use regex::Regex;
+use serde::Deserialize;
+use std::fs;
+use std::path::Path;
+
+/// What a rule matches against a scanned file's content.
+pub enum RuleMatch {
+    Regex(Regex),
+    Contains(String),
+}
+
+pub struct Rule {
+    pub id: String,
+    pub matcher: RuleMatch,
+    pub severity: String,
+    pub message: String,
+    pub owasp: Option<String>,
+    /// File-path glob this rule applies to, e.g. `"**/*.ts"`. `None` applies
+    /// to every scanned file.
+    pub glob: Option<Glob>,
+    /// A substring whose presence in the same content suppresses the match,
+    /// mirroring the built-in checks' `!content.contains("zod")`-style
+    /// guards (e.g. a rule flagging unchecked JSON.parse might guard on a
+    /// nearby schema validator import).
+    pub negative_guard: Option<String>,
+}
+
+impl Rule {
+    pub fn applies_to(&self, file: &str) -> bool {
+        match &self.glob {
+            Some(glob) => glob.compile_matcher().is_match(file),
+            None => true,
+        }
+    }
+
+    pub fn is_match(&self, line: &str) -> bool {
+        let matched = match &self.matcher {
+            RuleMatch::Regex(re) => re.is_match(line),
+            RuleMatch::Contains(needle) => line.contains(needle.as_str()),
+        };
+        let suppressed = self.negative_guard.as_deref().is_some_and(|guard| line.contains(guard));
+        matched && !suppressed
+    }
+}
+
+#[derive(Deserialize)]
+struct RawRule {
+    id: String,
+    #[serde(default)]
+    pattern: Option<String>,
+    #[serde(default)]
+    contains: Option<String>,
+    severity: String,
+    message: String,
+    #[serde(default)]
+    owasp: Option<String>,
+    #[serde(default)]
+    glob: Option<String>,
+    #[serde(default)]
+    negative_guard: Option<String>,
+}
+
+impl RawRule {
+    fn into_rule(self) -> Result<Rule> {
+        let matcher = match (self.pattern, self.contains) {
+            (Some(pattern), _) => RuleMatch::Regex(
+                Regex::new(&pattern).with_context(|| format!("rule '{}': invalid regex", self.id))?,
+            ),
+            (None, Some(contains)) => RuleMatch::Contains(contains),
+            (None, None) => anyhow::bail!("rule '{}' needs a `pattern` or `contains`", self.id),
+        };
+
+        let glob = self
+            .glob
+            .map(|g| Glob::new(&g).with_context(|| format!("rule '{}': invalid glob '{}'", self.id, g)))
+            .transpose()?;
+
+        Ok(Rule {
+            id: self.id,
+            matcher,
+            severity: self.severity,
+            message: self.message,
+            owasp: self.owasp,
+            glob,
+            negative_guard: self.negative_guard,
+        })
+    }
+}
+
+/// Loads `.foodrules.json` or `.foodrules.yaml`/`.yml` from `dir`, in that
+/// order, returning an empty rule set when neither is present.
+pub fn load(dir: &Path) -> Result<Vec<Rule>> {
+    if let Some(raw) = read_first_existing(dir, &["foodrules.json"]) {
+        let rules: Vec<RawRule> = serde_json::from_str(&raw).context("parsing .foodrules.json")?;
+        return rules.into_iter().map(RawRule::into_rule).collect();
+    }
+
+    if let Some(raw) = read_first_existing(dir, &["foodrules.yaml", "foodrules.yml"]) {
+        return parse_yaml_rules(&raw)?.into_iter().map(RawRule::into_rule).collect();
+    }
+
+    Ok(Vec::new())
+}
+
+fn read_first_existing(dir: &Path, names: &[&str]) -> Option<String> {
+    names.iter().map(|n| dir.join(format!(".{n}"))).find(|p| p.exists()).and_then(|p| fs::read_to_string(p).ok())
+}
+
+/// Hand-rolled parser for the one YAML shape rule packs use: a top-level
+/// list of flat string-keyed maps (`- id: ...` followed by indented
+/// `key: value` lines). No YAML crate is in use anywhere else in this
+/// codebase, so this avoids adding a dependency just for rule packs, the
+/// same call made for `pnpm-lock.yaml` in `osv_advisories`.
+fn parse_yaml_rules(raw: &str) -> Result<Vec<RawRule>> {
+    let mut entries: Vec<serde_json::Map<String, serde_json::Value>> = Vec::new();
+
+    for line in raw.lines() {
+        let trimmed = line.trim_start();
+        if trimmed.is_empty() || trimmed.starts_with('#') {
+            continue;
+        }
+
+        if let Some(rest) = trimmed.strip_prefix("- ") {
+            entries.push(serde_json::Map::new());
+            push_yaml_pair(entries.last_mut().unwrap(), rest)?;
+        } else if let Some(entry) = entries.last_mut() {
+            push_yaml_pair(entry, trimmed)?;
+        }
+    }
+
+    entries
+        .into_iter()
+        .map(|m| serde_json::from_value(serde_json::Value::Object(m)).context("parsing .foodrules.yaml entry"))
+        .collect()
+}
+
+fn push_yaml_pair(entry: &mut serde_json::Map<String, serde_json::Value>, line: &str) -> Result<()> {
+    let (key, value) = line.split_once(':').with_context(|| format!("expected 'key: value', got '{line}'"))?;
+    let value = value.trim().trim_matches('"').trim_matches('\'');
+    entry.insert(key.trim().to_string(), serde_json::Value::String(value.to_string()));
+    Ok(())
+}
@@ -0,0 +1,77 @@
+use anyhow::{bail, Result};
+use regex::Regex;
+use std::fs;
+use std::path::Path;
+
+/// One parsed line of `.foodcheck-secrets-ignore`. Modeled on Mercurial's
+/// narrowspec files: a small set of validated prefixes rather than free-form
+/// globs, so the baseline stays predictable.
+pub enum IgnoreEntry {
+    /// `path:<exact file>` — suppress one specific file entirely.
+    Path(String),
+    /// `rootfilesin:<dir>` — suppress that directory's direct children only
+    /// (not nested subdirectories).
+    RootFilesIn(String),
+    /// `regex:<pattern>` — whitelist any matched secret text the pattern
+    /// matches, wherever it's found.
+    Regex(Regex),
+}
+
+/// Parses `.foodcheck-secrets-ignore`, rejecting any line with an
+/// unrecognized prefix so a typo'd entry fails loudly instead of silently
+/// matching nothing.
+pub fn load(path: &Path) -> Result<Vec<IgnoreEntry>> {
+    if !path.exists() {
+        return Ok(Vec::new());
+    }
+
+    let content = fs::read_to_string(path)?;
+    let mut entries = Vec::new();
+
+    for (i, raw_line) in content.lines().enumerate() {
+        let line = raw_line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+
+        if let Some(rest) = line.strip_prefix("path:") {
+            entries.push(IgnoreEntry::Path(rest.to_string()));
+        } else if let Some(rest) = line.strip_prefix("rootfilesin:") {
+            entries.push(IgnoreEntry::RootFilesIn(rest.trim_end_matches('/').to_string()));
+        } else if let Some(rest) = line.strip_prefix("regex:") {
+            entries.push(IgnoreEntry::Regex(Regex::new(rest)?));
+        } else {
+            bail!(
+                "{}:{}: unrecognized pattern prefix in '{}' (expected path:, rootfilesin:, or regex:)",
+                path.display(),
+                i + 1,
+                line
+            );
+        }
+    }
+
+    Ok(entries)
+}
+
+/// A DifferenceMatcher: a file is scanned when it's in the incoming scan set
+/// *and not* matched by any `path:`/`rootfilesin:` ignore entry.
+pub fn is_file_baselined(entries: &[IgnoreEntry], file: &str) -> bool {
+    entries.iter().any(|e| match e {
+        IgnoreEntry::Path(p) => p == file,
+        IgnoreEntry::RootFilesIn(dir) => Path::new(file)
+            .parent()
+            .map(|p| p.to_string_lossy() == dir.as_str())
+            .unwrap_or(false),
+        IgnoreEntry::Regex(_) => false,
+    })
+}
+
+/// Whether the matched secret text itself is whitelisted by a `regex:`
+/// entry, letting a single known false positive be suppressed without
+/// dropping the whole file.
+pub fn is_match_baselined(entries: &[IgnoreEntry], matched_text: &str) -> bool {
+    entries.iter().any(|e| match e {
+        IgnoreEntry::Regex(re) => re.is_match(matched_text),
+        _ => false,
+    })
+}
@@ -0,0 +1,60 @@
+//! `.securityignore.json`: lets an accepted/reviewed `nextjs_security`
+//! finding be silenced without disabling the check for everyone forever.
+//! Modeled on `.nsprc`-style advisory allowlists — entries are keyed by a
+//! content fingerprint rather than a line number, so they survive unrelated
+//! edits to the file they reference.
+
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+use std::collections::hash_map::DefaultHasher;
+use std::fs;
+use std::hash::{Hash, Hasher};
+use std::path::Path;
+
+#[derive(Serialize, Deserialize)]
+pub struct BaselineEntry {
+    pub fingerprint: String,
+    #[serde(default)]
+    pub reason: Option<String>,
+    /// ISO `YYYY-MM-DD`. Past this date the suppression stops applying, so
+    /// accepted debt doesn't get silenced indefinitely by accident.
+    #[serde(default)]
+    pub expires: Option<String>,
+}
+
+/// Parses `.securityignore.json`. A missing file means no suppressions.
+pub fn load(path: &Path) -> Result<Vec<BaselineEntry>> {
+    if !path.exists() {
+        return Ok(Vec::new());
+    }
+
+    let content = fs::read_to_string(path)?;
+    Ok(serde_json::from_str(&content)?)
+}
+
+/// Writes `entries` to `path` as pretty-printed JSON, overwriting any
+/// existing baseline - `--write-baseline` is an explicit "accept the current
+/// state" action, not a merge.
+pub fn write(path: &Path, entries: &[BaselineEntry]) -> Result<()> {
+    let json = serde_json::to_string_pretty(entries)?;
+    fs::write(path, json)?;
+    Ok(())
+}
+
+/// Fingerprints `(rule id, normalized file path, message)` — the same
+/// identity a baseline entry is keyed on — so a finding can be matched back
+/// to its suppression across runs without storing line numbers.
+pub fn fingerprint(rule_id: &str, file: &str, message: &str) -> String {
+    let mut hasher = DefaultHasher::new();
+    rule_id.hash(&mut hasher);
+    file.replace('\\', "/").hash(&mut hasher);
+    message.hash(&mut hasher);
+    format!("{:016x}", hasher.finish())
+}
+
+/// Whether an `expires` date (ISO `YYYY-MM-DD`) has passed. ISO dates sort
+/// chronologically as strings, so this is a plain lexicographic compare
+/// against today rather than a calendar library.
+pub fn is_expired(expires: &str) -> bool {
+    !expires.is_empty() && expires < crate::utils::today_iso_date().as_str()
+}
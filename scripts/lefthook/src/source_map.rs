@@ -0,0 +1,189 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// One decoded mapping: a generated `(line, column)` paired with the
+/// original source it came from, when known.
+struct Segment {
+    generated_column: u32,
+    source_index: Option<u32>,
+    original_line: Option<u32>,
+    #[allow(dead_code)]
+    original_column: Option<u32>,
+}
+
+pub struct SourceMap {
+    sources: Vec<String>,
+    /// One `Vec<Segment>` per generated line, sorted by `generated_column`.
+    lines: Vec<Vec<Segment>>,
+}
+
+/// Loads the source map for `generated_file`: either an adjacent
+/// `<file>.map`, or one referenced by a trailing `//# sourceMappingURL=`
+/// comment (relative to `generated_file`'s directory). Returns `None` when
+/// neither is present — callers should fall back to the generated location.
+pub fn load_for(generated_file: &Path) -> Option<SourceMap> {
+    let adjacent = PathBuf::from(format!("{}.map", generated_file.display()));
+    if let Ok(content) = fs::read_to_string(&adjacent) {
+        return parse(&content, generated_file.parent()?);
+    }
+
+    let source = fs::read_to_string(generated_file).ok()?;
+    let url = source
+        .lines()
+        .rev()
+        .find_map(|line| line.trim_start().strip_prefix("//# sourceMappingURL="))?;
+    if url.starts_with("data:") {
+        let (_, b64) = url.split_once(',')?;
+        let decoded = base64_decode(b64)?;
+        let json = String::from_utf8(decoded).ok()?;
+        return parse(&json, generated_file.parent()?);
+    }
+
+    let map_path = generated_file.parent()?.join(url);
+    let content = fs::read_to_string(map_path).ok()?;
+    parse(&content, generated_file.parent()?)
+}
+
+fn parse(json: &str, _base_dir: &Path) -> Option<SourceMap> {
+    let value: serde_json::Value = serde_json::from_str(json).ok()?;
+    let sources: Vec<String> = value["sources"]
+        .as_array()?
+        .iter()
+        .map(|s| s.as_str().unwrap_or_default().to_string())
+        .collect();
+    let mappings_str = value["mappings"].as_str()?;
+
+    let lines = decode_mappings(mappings_str);
+
+    Some(SourceMap { sources, lines })
+}
+
+/// The original `(file, line)` for a generated `(line, column)`. Lines are
+/// 1-indexed; `generated_column` is 0-indexed, as in the mappings. Falls back to `(None, generated_line)` when the position
+/// isn't covered by any mapping — the standard "use the generated location"
+/// behavior for unmapped positions.
+pub fn original_position(map: &SourceMap, generated_line: u32, generated_column: u32) -> (Option<String>, u32) {
+    let Some(segments) = map.lines.get((generated_line.saturating_sub(1)) as usize) else {
+        return (None, generated_line);
+    };
+
+    // Segments are sorted ascending by generated_column; take the last one
+    // at or before our column (mappings describe "from here until the next
+    // segment"), matching how source-map consumers resolve a position.
+    let segment = segments
+        .iter()
+        .rev()
+        .find(|s| s.generated_column <= generated_column);
+
+    match segment {
+        Some(s) => {
+            let file = s.source_index.and_then(|i| map.sources.get(i as usize)).cloned();
+            let line = s.original_line.map(|l| l + 1).unwrap_or(generated_line);
+            (file, line)
+        }
+        None => (None, generated_line),
+    }
+}
+
+/// Decodes the `mappings` field: `;`-separated generated lines, each a
+/// `,`-separated list of VLQ-encoded segments. Every field in a segment
+/// after the first is a *delta* from the same field's previous value
+/// (columns reset per line; source index/line/column are running totals
+/// across the whole map).
+fn decode_mappings(mappings: &str) -> Vec<Vec<Segment>> {
+    let mut lines = Vec::new();
+    let (mut source_index, mut source_line, mut source_column) = (0i64, 0i64, 0i64);
+
+    for line_str in mappings.split(';') {
+        let mut segments = Vec::new();
+        let mut generated_column = 0i64;
+
+        for group in line_str.split(',') {
+            if group.is_empty() {
+                continue;
+            }
+            let fields = decode_vlq_group(group);
+            if fields.is_empty() {
+                continue;
+            }
+            generated_column += fields[0];
+
+            if fields.len() >= 4 {
+                source_index += fields[1];
+                source_line += fields[2];
+                source_column += fields[3];
+                segments.push(Segment {
+                    generated_column: generated_column.max(0) as u32,
+                    source_index: Some(source_index.max(0) as u32),
+                    original_line: Some(source_line.max(0) as u32),
+                    original_column: Some(source_column.max(0) as u32),
+                });
+            } else {
+                segments.push(Segment {
+                    generated_column: generated_column.max(0) as u32,
+                    source_index: None,
+                    original_line: None,
+                    original_column: None,
+                });
+            }
+        }
+
+        segments.sort_by_key(|s| s.generated_column);
+        lines.push(segments);
+    }
+
+    lines
+}
+
+const BASE64_ALPHABET: &[u8] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+
+fn base64_value(c: u8) -> Option<i64> {
+    BASE64_ALPHABET.iter().position(|&b| b == c).map(|i| i as i64)
+}
+
+/// Decodes one comma-separated group of base64-VLQ fields (e.g. a single
+/// mapping segment's `generatedColumn,sourceIndex,sourceLine,sourceColumn`).
+fn decode_vlq_group(group: &str) -> Vec<i64> {
+    let mut values = Vec::new();
+    let mut shift = 0u32;
+    let mut result: i64 = 0;
+
+    for &byte in group.as_bytes() {
+        let Some(digit) = base64_value(byte) else { continue };
+        let continuation = digit & 0x20 != 0;
+        let chunk = digit & 0x1f;
+        result += chunk << shift;
+        shift += 5;
+
+        if !continuation {
+            let negate = result & 1 != 0;
+            let value = result >> 1;
+            values.push(if negate { -value } else { value });
+            result = 0;
+            shift = 0;
+        }
+    }
+
+    values
+}
+
+fn base64_decode(s: &str) -> Option<Vec<u8>> {
+    let mut bits: u32 = 0;
+    let mut bit_count = 0u32;
+    let mut out = Vec::new();
+
+    for &byte in s.as_bytes() {
+        if byte == b'=' {
+            break;
+        }
+        let Some(value) = base64_value(byte) else { continue };
+        bits = (bits << 6) | value as u32;
+        bit_count += 6;
+        if bit_count >= 8 {
+            bit_count -= 8;
+            out.push((bits >> bit_count) as u8);
+        }
+    }
+
+    Some(out)
+}
@@ -0,0 +1,81 @@
+//! Subresource Integrity: computes and verifies sha256/384/512 digests for
+//! externally-referenced `<script>`/`<link>` tags, so `check_software_integrity`
+//! can suggest a ready-to-paste `integrity=` attribute instead of only
+//! warning one is missing, and catch a declared hash that no longer matches
+//! the bytes it's supposed to pin (supply-chain tampering).
+
+use base64::Engine;
+use sha2::{Digest, Sha256, Sha384, Sha512};
+use std::fs;
+use std::io::Read;
+use std::path::Path;
+
+/// One `alg-base64[?options]` token from an `integrity` attribute value.
+pub struct IntegrityToken {
+    pub algorithm: String,
+    pub digest_b64: String,
+}
+
+/// Parses a (possibly multi-value) `integrity` attribute into its tokens.
+pub fn parse_integrity(value: &str) -> Vec<IntegrityToken> {
+    value
+        .split_whitespace()
+        .filter_map(|token| {
+            let (alg, rest) = token.split_once('-')?;
+            let digest_b64 = rest.split('?').next().unwrap_or(rest);
+            Some(IntegrityToken { algorithm: alg.to_lowercase(), digest_b64: digest_b64.to_string() })
+        })
+        .collect()
+}
+
+/// SRI's "strongest metadata wins" rule: a weaker algorithm listed
+/// alongside a stronger one is ignored entirely rather than also checked.
+pub fn strongest(tokens: &[IntegrityToken]) -> Option<&IntegrityToken> {
+    ["sha512", "sha384", "sha256"]
+        .iter()
+        .find_map(|alg| tokens.iter().find(|t| t.algorithm == *alg))
+}
+
+/// Computes `algorithm` over `bytes`, standard-base64-encoded with padding.
+/// Returns `None` for an algorithm SRI doesn't recognize (e.g. a listed
+/// `sha1` or `md5` token some tool still emits).
+pub fn digest_base64(algorithm: &str, bytes: &[u8]) -> Option<String> {
+    let raw: Vec<u8> = match algorithm {
+        "sha256" => Sha256::digest(bytes).to_vec(),
+        "sha384" => Sha384::digest(bytes).to_vec(),
+        "sha512" => Sha512::digest(bytes).to_vec(),
+        _ => return None,
+    };
+    Some(base64::engine::general_purpose::STANDARD.encode(raw))
+}
+
+/// Builds a ready-to-paste `integrity="sha384-<b64>" crossorigin="anonymous"`
+/// suggestion for a tag that has no `integrity` attribute yet.
+pub fn suggest_attribute(bytes: &[u8]) -> Option<String> {
+    let digest = digest_base64("sha384", bytes)?;
+    Some(format!(r#"integrity="sha384-{digest}" crossorigin="anonymous""#))
+}
+
+/// Resolves the bytes an external `src`/`href` URL refers to: a locally
+/// vendored copy under `public/<basename>` when one exists, so an offline
+/// or CI run can still verify/suggest for assets that also ship in the
+/// repo, otherwise a live GET when `allow_network` is set. Returns `None`
+/// (not an error) on any fetch failure, since a missing/unreachable asset
+/// shouldn't block the rest of the scan - it just means no SRI suggestion.
+pub fn fetch_bytes(url: &str, allow_network: bool) -> Option<Vec<u8>> {
+    if let Some(basename) = url.rsplit('/').next().filter(|b| !b.is_empty()) {
+        let vendored = Path::new("public").join(basename);
+        if let Ok(bytes) = fs::read(vendored) {
+            return Some(bytes);
+        }
+    }
+
+    if !allow_network {
+        return None;
+    }
+
+    let response = ureq::get(url).call().ok()?;
+    let mut buf = Vec::new();
+    response.into_reader().read_to_end(&mut buf).ok()?;
+    Some(buf)
+}
@@ -3,6 +3,7 @@ use once_cell::sync::OnceCell;
 use std::process::Command;
 
 static VERBOSE: OnceCell<bool> = OnceCell::new();
+static NO_IGNORE: OnceCell<bool> = OnceCell::new();
 
 pub fn set_verbose(v: bool) {
     let _ = VERBOSE.set(v);
@@ -12,6 +13,17 @@ pub fn is_verbose() -> bool {
     *VERBOSE.get().unwrap_or(&false)
 }
 
+/// Set by the top-level `--no-ignore` flag: when true, discovery walks
+/// should also surface paths that `.gitignore`/`.git/info/exclude` would
+/// normally hide.
+pub fn set_no_ignore(v: bool) {
+    let _ = NO_IGNORE.set(v);
+}
+
+pub fn no_ignore() -> bool {
+    *NO_IGNORE.get().unwrap_or(&false)
+}
+
 // Output helpers
 pub fn print_header(title: &str) {
     println!();
@@ -67,6 +79,21 @@ pub fn get_staged_diff() -> String {
         .unwrap_or_default()
 }
 
+/// Same as `get_staged_diff`, scoped to a path set — used to drop baselined
+/// files out of the diff before scanning rather than after.
+pub fn get_staged_diff_for(files: &[String]) -> String {
+    if files.is_empty() {
+        return String::new();
+    }
+
+    Command::new("git")
+        .args(["diff", "--cached", "--"])
+        .args(files)
+        .output()
+        .map(|o| String::from_utf8_lossy(&o.stdout).to_string())
+        .unwrap_or_default()
+}
+
 pub fn get_current_branch() -> String {
     Command::new("git")
         .args(["branch", "--show-current"])
@@ -102,3 +129,53 @@ pub fn filter_files_by_extension(files: &[String], extensions: &[&str]) -> Vec<S
 pub fn is_test_file(file: &str) -> bool {
     file.contains(".test.") || file.contains(".spec.")
 }
+
+/// Today's date as `YYYY-MM-DD`, shelled out to `date` rather than pulling in
+/// a date/time crate for one comparison (baseline expiry).
+pub fn today_iso_date() -> String {
+    Command::new("date")
+        .arg("+%Y-%m-%d")
+        .output()
+        .map(|o| String::from_utf8_lossy(&o.stdout).trim().to_string())
+        .unwrap_or_default()
+}
+
+/// Runs `f` over every file in `files` across a small worker pool, in the
+/// spirit of `tidy`'s parallel directory walk - for a check whose per-file
+/// work is a blocking `fs::read_to_string` followed by some parsing, this
+/// overlaps that I/O across threads instead of doing it one file at a time.
+/// Results land back in `files`' original order regardless of which worker
+/// finishes first, so printed output stays deterministic. A panic in one
+/// file's closure is caught and becomes a `None` in that file's slot rather
+/// than aborting the rest of the batch.
+pub fn walk<T, F>(files: &[String], f: F) -> Vec<Option<T>>
+where
+    F: Fn(&str) -> T + Sync,
+    T: Send,
+{
+    if files.is_empty() {
+        return Vec::new();
+    }
+
+    let thread_count = std::thread::available_parallelism()
+        .map(|n| n.get())
+        .unwrap_or(4)
+        .min(files.len());
+    let next = std::sync::atomic::AtomicUsize::new(0);
+    let results: Vec<std::sync::Mutex<Option<T>>> = files.iter().map(|_| std::sync::Mutex::new(None)).collect();
+
+    std::thread::scope(|scope| {
+        for _ in 0..thread_count {
+            scope.spawn(|| loop {
+                let i = next.fetch_add(1, std::sync::atomic::Ordering::Relaxed);
+                if i >= files.len() {
+                    break;
+                }
+                let outcome = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| f(&files[i]))).ok();
+                *results[i].lock().unwrap() = outcome;
+            });
+        }
+    });
+
+    results.into_iter().map(|m| m.into_inner().unwrap()).collect()
+}
@@ -0,0 +1,73 @@
+use crate::utils::{get_staged_files, print_header};
+use anyhow::Result;
+use notify::{RecursiveMode, Watcher};
+use std::path::PathBuf;
+use std::sync::mpsc::{channel, RecvTimeoutError};
+use std::time::Duration;
+
+/// Debounce window for coalescing a burst of filesystem events into a
+/// single re-run, mirroring Deno's watch subcommands.
+const DEBOUNCE: Duration = Duration::from_millis(200);
+
+/// Runs `checker` once against `files`, then — if `watch` is set — stays
+/// resident, debounces filesystem bursts, recomputes the candidate file list
+/// via `recompute`, and re-invokes `checker` on each batch of changes. All
+/// watched paths are resolved against the cwd captured at startup so changes
+/// in subdirectories are never missed.
+pub fn run<F, R>(watch: bool, files: &[String], mut checker: F, mut recompute: R) -> Result<()>
+where
+    F: FnMut(&[String]) -> Result<()>,
+    R: FnMut() -> Vec<String>,
+{
+    let result = checker(files);
+    if !watch {
+        return result;
+    }
+
+    let cwd = std::env::current_dir()?;
+    let (tx, rx) = channel();
+    let mut watcher = notify::recommended_watcher(move |res| {
+        let _ = tx.send(res);
+    })?;
+    watcher.watch(&cwd, RecursiveMode::Recursive)?;
+
+    loop {
+        // Clear the terminal before each cycle's header, so a long-running
+        // watch session reads like a fresh run rather than an ever-growing
+        // scrollback, the same as Deno's test file-watcher.
+        print!("\x1B[2J\x1B[1;1H");
+        print_header("👀 Watching for changes…");
+
+        // Drain events until the burst goes quiet for one debounce window.
+        let mut saw_event = false;
+        loop {
+            match rx.recv_timeout(DEBOUNCE) {
+                Ok(Ok(_)) => saw_event = true,
+                Ok(Err(_)) => {}
+                Err(RecvTimeoutError::Timeout) => {
+                    if saw_event {
+                        break;
+                    }
+                }
+                Err(RecvTimeoutError::Disconnected) => return Ok(()),
+            }
+        }
+
+        let changed = recompute();
+        if changed.is_empty() {
+            continue;
+        }
+        let _ = checker(&changed);
+    }
+}
+
+/// Default `recompute` for hook-style checks: the currently staged files,
+/// resolved the same way the git-hook entrypoint does.
+pub fn staged_files() -> Vec<String> {
+    get_staged_files()
+}
+
+#[allow(dead_code)]
+pub fn canonical(paths: &[String]) -> Vec<PathBuf> {
+    paths.iter().map(PathBuf::from).collect()
+}
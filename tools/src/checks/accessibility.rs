@@ -1,8 +1,18 @@
-use crate::utils::{filter_files_by_extension, print_header, print_info, print_success, print_warning};
+use crate::report::{self, Finding, Severity};
+use crate::utils::{self, filter_files_by_extension, print_header, print_info, print_success, print_warning};
 use anyhow::Result;
 use regex::Regex;
 use std::fs;
 
+/// One flagged line, carrying everything `run` needs to print and emit it
+/// once the worker pool has finished the per-file scan.
+struct Hit {
+    line: usize,
+    rule: &'static str,
+    message: &'static str,
+    snippet: Option<String>,
+}
+
 pub fn run(files: &[String]) -> Result<()> {
     print_header("♿ Accessibility Check");
 
@@ -19,40 +29,49 @@ pub fn run(files: &[String]) -> Result<()> {
     let label_pattern = Regex::new(r"(aria-label=|id=)").unwrap();
     let div_onclick_pattern = Regex::new(r"<div[^>]*onClick=").unwrap();
 
-    let mut has_issues = false;
+    let results = utils::walk(&files, |file| -> Vec<Hit> {
+        let Ok(content) = fs::read_to_string(file) else { return Vec::new() };
+        let mut hits = Vec::new();
 
-    for file in &files {
-        if let Ok(content) = fs::read_to_string(file) {
-            let lines: Vec<&str> = content.lines().collect();
+        for (i, line) in content.lines().enumerate() {
+            // Check for images without alt text
+            if img_pattern.is_match(line) && !alt_pattern.is_match(line) {
+                hits.push(Hit { line: i + 1, rule: "img-missing-alt", message: "Image without alt text", snippet: Some(line.trim().to_string()) });
+            }
 
-            for (i, line) in lines.iter().enumerate() {
-                // Check for images without alt text
-                if img_pattern.is_match(line) && !alt_pattern.is_match(line) {
-                    print_warning(&format!("{}:{} Image without alt text", file, i + 1));
-                    println!("    {}", line.trim());
-                    has_issues = true;
-                }
+            // Check for inputs without labels
+            if input_pattern.is_match(line) && !label_pattern.is_match(line) {
+                hits.push(Hit {
+                    line: i + 1,
+                    rule: "input-missing-label",
+                    message: "Input should have aria-label or associated label",
+                    snippet: None,
+                });
+            }
 
-                // Check for inputs without labels
-                if input_pattern.is_match(line) && !label_pattern.is_match(line) {
-                    print_warning(&format!(
-                        "{}:{} Input should have aria-label or associated label",
-                        file,
-                        i + 1
-                    ));
-                    has_issues = true;
-                }
+            // Check for onClick on divs
+            if div_onclick_pattern.is_match(line) {
+                hits.push(Hit {
+                    line: i + 1,
+                    rule: "div-onclick",
+                    message: "onClick on div - consider using button or add role/keyboard handlers",
+                    snippet: None,
+                });
+            }
+        }
+
+        hits
+    });
 
-                // Check for onClick on divs
-                if div_onclick_pattern.is_match(line) {
-                    print_warning(&format!(
-                        "{}:{} onClick on div - consider using button or add role/keyboard handlers",
-                        file,
-                        i + 1
-                    ));
-                    has_issues = true;
-                }
+    let mut has_issues = false;
+    for (file, hits) in files.iter().zip(results) {
+        for hit in hits.unwrap_or_default() {
+            print_warning(&format!("{}:{} {}", file, hit.line, hit.message));
+            if let Some(snippet) = &hit.snippet {
+                println!("    {}", snippet);
             }
+            has_issues = true;
+            emit(file, hit.line, hit.rule, hit.message);
         }
     }
 
@@ -66,3 +85,15 @@ pub fn run(files: &[String]) -> Result<()> {
     // Warning only, not blocking
     Ok(())
 }
+
+fn emit(file: &str, line: usize, rule: &str, message: &str) {
+    report::push(Finding {
+        check: "accessibility".to_string(),
+        file: file.to_string(),
+        line: Some(line as u32),
+        severity: Severity::Warning,
+        rule: rule.to_string(),
+        message: message.to_string(),
+        owasp: None,
+    });
+}
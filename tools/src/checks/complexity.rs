@@ -1,11 +1,22 @@
-use crate::utils::{filter_files_by_extension, print_header, print_info, print_success, print_warning};
+use crate::report::{self, Finding, Severity};
+use crate::source_map;
+use crate::utils::{self, filter_files_by_extension, print_header, print_info, print_success, print_warning};
 use anyhow::Result;
 use regex::Regex;
 use std::fs;
+use std::path::Path;
 
 const MAX_FUNCTION_LINES: usize = 100;
 const MAX_NESTING_LEVEL: usize = 5;
 
+/// One flagged finding, already resolved through the source map if the
+/// offending file is build output - everything `run` needs to print and
+/// emit once the worker pool has finished scanning.
+enum Hit {
+    ExcessiveNesting { count: usize },
+    LongFunction { file: String, line: usize, length: usize },
+}
+
 pub fn run(files: &[String]) -> Result<()> {
     print_header("🧮 Code Complexity Check");
 
@@ -16,57 +27,71 @@ pub fn run(files: &[String]) -> Result<()> {
         return Ok(());
     }
 
-    let mut has_issues = false;
     let deep_nesting_pattern = Regex::new(r"^\s{8,}(if|for|while|switch)").unwrap();
     let function_start_pattern = Regex::new(r"^\s*(function|const.*=.*\(|=>)").unwrap();
 
-    for file in &files {
-        if let Ok(content) = fs::read_to_string(file) {
-            let lines: Vec<&str> = content.lines().collect();
-
-            // Check for deep nesting
-            let nesting_count = lines
-                .iter()
-                .filter(|line| deep_nesting_pattern.is_match(line))
-                .count();
-
-            if nesting_count > MAX_NESTING_LEVEL {
-                print_warning(&format!(
-                    "{}: Excessive nesting detected ({} deep levels)",
-                    file, nesting_count
-                ));
-                has_issues = true;
+    let results = utils::walk(&files, |file| -> Vec<Hit> {
+        let Ok(content) = fs::read_to_string(file) else { return Vec::new() };
+        let lines: Vec<&str> = content.lines().collect();
+        let mut hits = Vec::new();
+
+        // Check for deep nesting
+        let nesting_count = lines
+            .iter()
+            .filter(|line| deep_nesting_pattern.is_match(line))
+            .count();
+
+        if nesting_count > MAX_NESTING_LEVEL {
+            hits.push(Hit::ExcessiveNesting { count: nesting_count });
+        }
+
+        // Check for long functions (simplified heuristic)
+        let mut in_function = false;
+        let mut function_start = 0;
+        let mut brace_count = 0;
+
+        for (i, line) in lines.iter().enumerate() {
+            if function_start_pattern.is_match(line) && !in_function {
+                in_function = true;
+                function_start = i;
+                brace_count = 0;
             }
 
-            // Check for long functions (simplified heuristic)
-            let mut in_function = false;
-            let mut function_start = 0;
-            let mut brace_count = 0;
+            if in_function {
+                brace_count += line.matches('{').count() as i32;
+                brace_count -= line.matches('}').count() as i32;
 
-            for (i, line) in lines.iter().enumerate() {
-                if function_start_pattern.is_match(line) && !in_function {
-                    in_function = true;
-                    function_start = i;
-                    brace_count = 0;
+                if brace_count <= 0 && i > function_start {
+                    let function_length = i - function_start;
+                    if function_length > MAX_FUNCTION_LINES {
+                        // `file` may be build output rather than the file the
+                        // developer edits (e.g. `.next/`); translate through its
+                        // source map when one exists so the warning still points
+                        // at the authored TypeScript.
+                        let (report_file, report_line) = resolve_location(file, function_start + 1);
+                        hits.push(Hit::LongFunction { file: report_file, line: report_line, length: function_length });
+                    }
+                    in_function = false;
                 }
+            }
+        }
 
-                if in_function {
-                    brace_count += line.matches('{').count() as i32;
-                    brace_count -= line.matches('}').count() as i32;
-
-                    if brace_count <= 0 && i > function_start {
-                        let function_length = i - function_start;
-                        if function_length > MAX_FUNCTION_LINES {
-                            print_warning(&format!(
-                                "{}: Function at line {} is too long ({} lines)",
-                                file,
-                                function_start + 1,
-                                function_length
-                            ));
-                            has_issues = true;
-                        }
-                        in_function = false;
-                    }
+        hits
+    });
+
+    let mut has_issues = false;
+    for (file, hits) in files.iter().zip(results) {
+        for hit in hits.unwrap_or_default() {
+            match hit {
+                Hit::ExcessiveNesting { count } => {
+                    print_warning(&format!("{}: Excessive nesting detected ({} deep levels)", file, count));
+                    has_issues = true;
+                    emit(file, None, "excessive-nesting", &format!("Excessive nesting detected ({} deep levels)", count));
+                }
+                Hit::LongFunction { file: report_file, line: report_line, length } => {
+                    print_warning(&format!("{}: Function at line {} is too long ({} lines)", report_file, report_line, length));
+                    has_issues = true;
+                    emit(&report_file, Some(report_line), "long-function", &format!("Function is too long ({} lines)", length));
                 }
             }
         }
@@ -82,3 +107,28 @@ pub fn run(files: &[String]) -> Result<()> {
     // Complexity check is a warning, not a blocker
     Ok(())
 }
+
+/// Maps a `(file, line)` through that file's source map, when one exists,
+/// returning the original source location; otherwise returns the input
+/// unchanged (the common case for hand-written source files).
+fn resolve_location(file: &str, line: usize) -> (String, usize) {
+    match source_map::load_for(Path::new(file)) {
+        Some(map) => {
+            let (original_file, original_line) = source_map::original_position(&map, line as u32, 0);
+            (original_file.unwrap_or_else(|| file.to_string()), original_line as usize)
+        }
+        None => (file.to_string(), line),
+    }
+}
+
+fn emit(file: &str, line: Option<usize>, rule: &str, message: &str) {
+    report::push(Finding {
+        check: "complexity".to_string(),
+        file: file.to_string(),
+        line: line.map(|l| l as u32),
+        severity: Severity::Warning,
+        rule: rule.to_string(),
+        message: message.to_string(),
+        owasp: None,
+    });
+}
@@ -1,9 +1,22 @@
-use crate::utils::{filter_files_by_extension, print_header, print_info, print_success, print_warning};
+use crate::report::{self, Finding, Severity};
+use crate::utils::{self, filter_files_by_extension, print_header, print_info, print_success, print_warning};
 use anyhow::Result;
 use regex::Regex;
 use std::fs;
+use std::process::Command;
 
-pub fn run(files: &[String]) -> Result<()> {
+/// A file's per-file analysis, computed once on the worker pool and handed
+/// back for serial printing/emitting (and the `--fix` rewrite, which touches
+/// the filesystem and git index and so stays off the pool).
+struct FileAnalysis {
+    deep_imports: Vec<(usize, String)>,
+    mixed_import_style: bool,
+    content: String,
+    imports: Vec<ImportStmt>,
+    order_issue: Option<Offender>,
+}
+
+pub fn run(files: &[String], fix: bool) -> Result<()> {
     print_header("📦 Import Organization Check");
 
     let files = filter_files_by_extension(files, &[".ts", ".tsx", ".js", ".jsx"]);
@@ -17,45 +30,80 @@ pub fn run(files: &[String]) -> Result<()> {
     let import_pattern = Regex::new(r"^import ").unwrap();
     let require_pattern = Regex::new(r"require\(").unwrap();
 
+    let results = utils::walk(&files, |file| -> Option<FileAnalysis> {
+        let content = fs::read_to_string(file).ok()?;
+
+        let deep_imports: Vec<(usize, String)> = content
+            .lines()
+            .enumerate()
+            .filter(|(_, line)| deep_import_pattern.is_match(line))
+            .map(|(i, line)| (i, line.to_string()))
+            .collect();
+
+        let has_import = content.lines().any(|line| import_pattern.is_match(line));
+        let has_require = content.lines().any(|line| require_pattern.is_match(line));
+
+        // Check that the leading import block is grouped (external, then
+        // @/ aliases, then relative) and alphabetically sorted within each
+        // group, the same ordering `eslint-plugin-import`'s `order` rule
+        // enforces.
+        let imports = leading_imports(&content);
+        let order_issue = first_out_of_order(&imports);
+
+        Some(FileAnalysis { deep_imports, mixed_import_style: has_import && has_require, content, imports, order_issue })
+    });
+
     let mut has_issues = false;
+    let mut fixed_files = Vec::new();
 
-    for file in &files {
-        if let Ok(content) = fs::read_to_string(file) {
-            // Check for deep relative imports
-            let deep_imports: Vec<(usize, &str)> = content
-                .lines()
-                .enumerate()
-                .filter(|(_, line)| deep_import_pattern.is_match(line))
-                .collect();
-
-            if !deep_imports.is_empty() {
-                print_warning(&format!(
-                    "{}: Deep relative imports found (consider using absolute imports):",
-                    file
-                ));
-                for (line_num, line) in deep_imports.iter().take(3) {
-                    println!("    {}:{}", line_num + 1, line.trim());
-                }
-                has_issues = true;
+    for (file, analysis) in files.iter().zip(results) {
+        let Some(analysis) = analysis.flatten() else { continue };
+
+        if !analysis.deep_imports.is_empty() {
+            print_warning(&format!(
+                "{}: Deep relative imports found (consider using absolute imports):",
+                file
+            ));
+            for (line_num, line) in analysis.deep_imports.iter().take(3) {
+                println!("    {}:{}", line_num + 1, line.trim());
+                emit(file, Some(line_num + 1), "deep-relative-import", "Deep relative import (consider an absolute import)");
             }
+            has_issues = true;
+        }
+
+        if analysis.mixed_import_style {
+            print_warning(&format!(
+                "{}: Mixed import styles (import and require)",
+                file
+            ));
+            has_issues = true;
+            emit(file, None, "mixed-import-style", "Mixed import styles (import and require)");
+        }
 
-            // Check for mixed import styles
-            let has_import = content.lines().any(|line| import_pattern.is_match(line));
-            let has_require = content.lines().any(|line| require_pattern.is_match(line));
+        if let Some(issue) = &analysis.order_issue {
+            has_issues = true;
+            emit(file, Some(issue.line as usize), "import-order", &issue.message);
 
-            if has_import && has_require {
-                print_warning(&format!(
-                    "{}: Mixed import styles (import and require)",
-                    file
-                ));
-                has_issues = true;
+            if fix {
+                rewrite(file, &analysis.content, &analysis.imports)?;
+                fixed_files.push(file.clone());
+            } else {
+                print_warning(&format!("{}:{} {}", file, issue.line, issue.message));
             }
         }
     }
 
+    if fix && !fixed_files.is_empty() {
+        print_info(&format!("Rewrote {} file(s) with out-of-order imports", fixed_files.len()));
+        restage(&fixed_files)?;
+    }
+
     if has_issues {
         println!();
         print_info("Consider organizing imports using absolute paths (@/) and consistent import style");
+        if !fix {
+            print_info("Run with --fix to auto-sort grouped imports");
+        }
     } else {
         print_success("Import organization looks good");
     }
@@ -63,3 +111,233 @@ pub fn run(files: &[String]) -> Result<()> {
     // Warning only, not blocking
     Ok(())
 }
+
+fn emit(file: &str, line: Option<usize>, rule: &str, message: &str) {
+    report::push(Finding {
+        check: "import_check".to_string(),
+        file: file.to_string(),
+        line: line.map(|l| l as u32),
+        severity: Severity::Warning,
+        rule: rule.to_string(),
+        message: message.to_string(),
+        owasp: None,
+    });
+}
+
+/// The order `eslint-plugin-import` expects: third-party packages first,
+/// then `@/`-aliased absolute imports, then relative `./`/`../` imports.
+#[derive(PartialEq, Eq, PartialOrd, Ord, Clone, Copy, Debug)]
+enum Group {
+    External,
+    Alias,
+    Relative,
+}
+
+struct ImportStmt {
+    line: u32,
+    raw: String,
+    specifier: String,
+    group: Group,
+    side_effect: bool,
+}
+
+struct Offender {
+    line: u32,
+    message: String,
+}
+
+/// Parses the leading contiguous block of `import` statements out of
+/// `content`: blank lines separate groups but don't end the block, `//` and
+/// `/* */` comments are skipped over, and scanning stops at the first line
+/// that is neither blank, a comment, nor the start/continuation of an
+/// `import` statement.
+fn leading_imports(content: &str) -> Vec<ImportStmt> {
+    let from_pattern = Regex::new(r#"from\s+['"]([^'"]+)['"]"#).unwrap();
+    let side_effect_pattern = Regex::new(r#"^import\s+['"]([^'"]+)['"]"#).unwrap();
+
+    let mut imports = Vec::new();
+    let mut lines = content.lines().enumerate();
+    let mut pending: Option<(u32, String, i32)> = None; // (start_line, raw, brace depth)
+
+    while let Some((idx, line)) = lines.next() {
+        if let Some((start_line, raw, depth)) = pending.as_mut() {
+            raw.push('\n');
+            raw.push_str(line);
+            *depth += brace_delta(line);
+            if *depth <= 0 && line.trim_end().ends_with(';') {
+                if let Some(stmt) = parse_import(*start_line, raw, &from_pattern, &side_effect_pattern) {
+                    imports.push(stmt);
+                }
+                pending = None;
+            }
+            continue;
+        }
+
+        let trimmed = line.trim();
+
+        if trimmed.is_empty() || trimmed.starts_with("//") {
+            continue;
+        }
+        if trimmed.starts_with("/*") {
+            if !trimmed.contains("*/") {
+                for (_, rest) in lines.by_ref() {
+                    if rest.contains("*/") {
+                        break;
+                    }
+                }
+            }
+            continue;
+        }
+        if !trimmed.starts_with("import ") && !trimmed.starts_with("import{") {
+            break;
+        }
+
+        let depth = brace_delta(line);
+        if depth <= 0 && line.trim_end().ends_with(';') {
+            if let Some(stmt) = parse_import(idx as u32 + 1, line, &from_pattern, &side_effect_pattern) {
+                imports.push(stmt);
+            }
+        } else {
+            pending = Some((idx as u32 + 1, line.to_string(), depth));
+        }
+    }
+
+    imports
+}
+
+fn brace_delta(line: &str) -> i32 {
+    line.matches('{').count() as i32 - line.matches('}').count() as i32
+}
+
+fn parse_import(line: u32, raw: &str, from_pattern: &Regex, side_effect_pattern: &Regex) -> Option<ImportStmt> {
+    let flattened = raw.split_whitespace().collect::<Vec<_>>().join(" ");
+
+    if let Some(caps) = from_pattern.captures(&flattened) {
+        let specifier = caps[1].to_string();
+        return Some(ImportStmt { line, raw: raw.to_string(), group: group_of(&specifier), specifier, side_effect: false });
+    }
+    if let Some(caps) = side_effect_pattern.captures(flattened.trim()) {
+        let specifier = caps[1].to_string();
+        return Some(ImportStmt { line, raw: raw.to_string(), group: group_of(&specifier), specifier, side_effect: true });
+    }
+    None
+}
+
+fn group_of(specifier: &str) -> Group {
+    if specifier.starts_with("./") || specifier.starts_with("../") {
+        Group::Relative
+    } else if specifier.starts_with("@/") {
+        Group::Alias
+    } else {
+        Group::External
+    }
+}
+
+/// Walks `imports` in file order and reports the first adjacent pair that
+/// violates either grouping (a later group appearing before an earlier one)
+/// or, within a group, case-insensitive ascending order. Side-effect imports
+/// are skipped for the ordering comparison; `rewrite` moves them to the
+/// front of their group.
+fn first_out_of_order(imports: &[ImportStmt]) -> Option<Offender> {
+    let mut prev: Option<&ImportStmt> = None;
+
+    for stmt in imports {
+        if stmt.side_effect {
+            continue;
+        }
+
+        if let Some(prev) = prev {
+            if stmt.group < prev.group {
+                return Some(Offender {
+                    line: stmt.line,
+                    message: format!(
+                        "'{}' ({:?}) should be grouped before '{}' ({:?})",
+                        stmt.specifier, stmt.group, prev.specifier, prev.group
+                    ),
+                });
+            }
+            if stmt.group == prev.group && stmt.specifier.to_lowercase() < prev.specifier.to_lowercase() {
+                return Some(Offender {
+                    line: stmt.line,
+                    message: format!(
+                        "'{}' should come before '{}' (alphabetical order within the group)",
+                        stmt.specifier, prev.specifier
+                    ),
+                });
+            }
+        }
+
+        prev = Some(stmt);
+    }
+
+    None
+}
+
+/// Rewrites the leading import block into canonical grouped/sorted form: one
+/// blank line between the external/alias/relative groups, each group sorted
+/// case-insensitively by specifier, with side-effect imports (which have no
+/// bound name to read at the call site) sorted ahead of the rest of their
+/// group rather than mixed in with them.
+fn rewrite(file: &str, content: &str, imports: &[ImportStmt]) -> Result<()> {
+    let Some(first) = imports.first() else { return Ok(()) };
+    let Some(last) = imports.last() else { return Ok(()) };
+
+    let lines: Vec<&str> = content.lines().collect();
+    let before = lines[..(first.line as usize - 1)].join("\n");
+    let after_start = (last.line as usize - 1) + last.raw.lines().count();
+    let after = lines[after_start..].join("\n");
+
+    let mut by_group: Vec<(Group, Vec<&ImportStmt>)> =
+        vec![(Group::External, Vec::new()), (Group::Alias, Vec::new()), (Group::Relative, Vec::new())];
+    for stmt in imports {
+        by_group.iter_mut().find(|(g, _)| *g == stmt.group).unwrap().1.push(stmt);
+    }
+
+    let mut block = String::new();
+    let mut first_group = true;
+    for (_, mut stmts) in by_group {
+        if stmts.is_empty() {
+            continue;
+        }
+        stmts.sort_by(|a, b| {
+            b.side_effect.cmp(&a.side_effect).then_with(|| a.specifier.to_lowercase().cmp(&b.specifier.to_lowercase()))
+        });
+
+        if !first_group {
+            block.push('\n');
+        }
+        first_group = false;
+
+        for stmt in stmts {
+            block.push_str(&stmt.raw);
+            block.push('\n');
+        }
+    }
+
+    let mut rewritten = String::new();
+    if !before.is_empty() {
+        rewritten.push_str(&before);
+        rewritten.push('\n');
+    }
+    rewritten.push_str(block.trim_end());
+    rewritten.push('\n');
+    let after_trimmed = after.trim_start_matches('\n');
+    if !after_trimmed.is_empty() {
+        rewritten.push('\n');
+        rewritten.push_str(after_trimmed);
+        if !rewritten.ends_with('\n') {
+            rewritten.push('\n');
+        }
+    }
+
+    fs::write(file, rewritten)?;
+    Ok(())
+}
+
+/// `--fix` rewrites working-tree files, so re-stage them the same way a
+/// formatter hook would - otherwise the fixed content wouldn't make it into
+/// the commit the hook is gating.
+fn restage(files: &[String]) -> Result<()> {
+    Command::new("git").arg("add").args(files).status()?;
+    Ok(())
+}
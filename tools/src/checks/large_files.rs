@@ -1,5 +1,6 @@
 use crate::utils::{format_bytes, get_staged_files, print_error, print_header, print_info, print_success, print_warning};
 use anyhow::Result;
+use globset::Glob;
 use std::fs;
 use std::path::Path;
 
@@ -13,34 +14,147 @@ pub fn run(max_size_kb: u64) -> Result<()> {
         return Ok(());
     }
 
+    let rules = load_gitattributes(Path::new(".gitattributes"));
     let max_size_bytes = max_size_kb * 1024;
-    let mut large_files = Vec::new();
+
+    let mut oversized = Vec::new();
+    let mut binaries = Vec::new();
 
     for file in &staged_files {
         let path = Path::new(file);
-        if path.exists() {
-            if let Ok(metadata) = fs::metadata(path) {
-                let size = metadata.len();
-                if size > max_size_bytes {
-                    large_files.push((file.clone(), size));
-                }
+        if !path.exists() {
+            continue;
+        }
+
+        let attrs = resolve_attrs(&rules, file);
+        if attrs.lfs {
+            // Already tracked by Git LFS - that's the fix we'd otherwise suggest.
+            continue;
+        }
+
+        if let Ok(metadata) = fs::metadata(path) {
+            if metadata.len() > max_size_bytes {
+                oversized.push((file.clone(), metadata.len()));
             }
         }
+
+        if attrs.binary || looks_binary(path) {
+            binaries.push(file.clone());
+        }
     }
 
-    if large_files.is_empty() {
-        print_success("No large files detected");
+    if oversized.is_empty() && binaries.is_empty() {
+        print_success("No large or untracked-binary files detected");
         return Ok(());
     }
 
-    print_warning(&format!("Large files detected (>{}KB):", max_size_kb));
-    for (file, size) in &large_files {
-        print_error(&format!("  {} ({})", file, format_bytes(*size)));
+    if !oversized.is_empty() {
+        print_warning(&format!("Large files detected (>{}KB):", max_size_kb));
+        for (file, size) in &oversized {
+            print_error(&format!("  {} ({}) - {}", file, format_bytes(*size), lfs_track_suggestion(file)));
+        }
+    }
+
+    if !binaries.is_empty() {
+        print_warning("Binary files detected (committing binaries to history is the real problem, regardless of size):");
+        for file in &binaries {
+            if !oversized.iter().any(|(f, _)| f == file) {
+                print_error(&format!("  {} - {}", file, lfs_track_suggestion(file)));
+            }
+        }
     }
 
     println!();
-    print_info("Consider using Git LFS for large binary files");
-    print_info("Install: git lfs install && git lfs track '*.large-extension'");
+    print_info("Track these with Git LFS instead of committing them directly into history");
+    print_info("Install: git lfs install");
+
+    Err(anyhow::anyhow!("Large or binary files detected"))
+}
+
+/// The subset of `.gitattributes` fields this check cares about, resolved
+/// for one path: is it already under `filter=lfs`, and is it marked
+/// `binary`/`-text` (both of which disable diffing the same way).
+#[derive(Default)]
+struct PathAttrs {
+    lfs: bool,
+    binary: bool,
+}
+
+struct AttrRule {
+    matcher: globset::GlobMatcher,
+    // `None` means this rule's line didn't mention the attribute at all, as
+    // opposed to explicitly unsetting it - only a later rule that actually
+    // mentions the attribute should override an earlier one.
+    lfs: Option<bool>,
+    binary: Option<bool>,
+}
+
+/// Parses a `.gitattributes` file into pattern -> attribute rules, the same
+/// shape gitoxide's attribute stack resolves against, simplified to
+/// first-pass single-file "later rule wins" since this tree has no nested
+/// `.gitattributes` support to match.
+fn load_gitattributes(path: &Path) -> Vec<AttrRule> {
+    let Ok(content) = fs::read_to_string(path) else { return Vec::new() };
+    let mut rules = Vec::new();
 
-    Err(anyhow::anyhow!("Large files detected"))
+    for line in content.lines() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+
+        let mut parts = line.split_whitespace();
+        let Some(pattern) = parts.next() else { continue };
+        let Ok(glob) = Glob::new(pattern) else { continue };
+
+        let mut lfs = None;
+        let mut binary = None;
+        for attr in parts {
+            match attr {
+                "filter=lfs" => lfs = Some(true),
+                "binary" | "-text" => binary = Some(true),
+                _ => {}
+            }
+        }
+
+        rules.push(AttrRule { matcher: glob.compile_matcher(), lfs, binary });
+    }
+
+    rules
+}
+
+/// Resolves `file`'s attributes by walking every matching rule in file
+/// order - for each attribute, the last matching rule that actually
+/// mentions it wins, same as real `.gitattributes` precedence.
+fn resolve_attrs(rules: &[AttrRule], file: &str) -> PathAttrs {
+    let mut attrs = PathAttrs::default();
+    for rule in rules {
+        if rule.matcher.is_match(file) {
+            if let Some(lfs) = rule.lfs {
+                attrs.lfs = lfs;
+            }
+            if let Some(binary) = rule.binary {
+                attrs.binary = binary;
+            }
+        }
+    }
+    attrs
+}
+
+/// NUL-byte heuristic over the first few KB, the same quick check `git`
+/// itself uses to decide whether a file "looks binary" when no attribute
+/// says otherwise.
+fn looks_binary(path: &Path) -> bool {
+    const SNIFF_BYTES: usize = 8192;
+    let Ok(bytes) = fs::read(path) else { return false };
+    bytes.iter().take(SNIFF_BYTES).any(|&b| b == 0)
+}
+
+/// A concrete `git lfs track` suggestion derived from the file's extension,
+/// rather than a generic placeholder pattern.
+fn lfs_track_suggestion(file: &str) -> String {
+    match Path::new(file).extension().and_then(|e| e.to_str()) {
+        Some(ext) => format!("git lfs track '*.{}'", ext),
+        None => format!("git lfs track '{}'", file),
+    }
 }
@@ -0,0 +1,25 @@
+pub mod accessibility;
+pub mod complexity;
+pub mod import_check;
+pub mod large_files;
+pub mod no_console;
+pub mod pre_commit;
+pub mod project_structure;
+pub mod protected_branch;
+pub mod test_coverage;
+
+// These checks live under scripts/lefthook/src/checks/ rather than
+// tools/src/checks/ - see tools/src/main.rs for the same split on the
+// top-level modules they depend on.
+#[path = "../../../scripts/lefthook/src/checks/bundle_size.rs"]
+pub mod bundle_size;
+#[path = "../../../scripts/lefthook/src/checks/conventional_commit.rs"]
+pub mod conventional_commit;
+#[path = "../../../scripts/lefthook/src/checks/dependency_audit.rs"]
+pub mod dependency_audit;
+#[path = "../../../scripts/lefthook/src/checks/nextjs_security.rs"]
+pub mod nextjs_security;
+#[path = "../../../scripts/lefthook/src/checks/security.rs"]
+pub mod security;
+#[path = "../../../scripts/lefthook/src/checks/unused_exports.rs"]
+pub mod unused_exports;
@@ -1,9 +1,12 @@
-use crate::utils::{filter_files_by_extension, is_test_file, print_header, print_info, print_success, print_warning};
+use crate::repo_config::NoConsoleConfig;
+use crate::report::{self, Finding, Severity};
+use crate::utils::{self, filter_files_by_extension, is_test_file, print_header, print_info, print_success, print_warning};
 use anyhow::Result;
+use globset::{Glob, GlobSet, GlobSetBuilder};
 use regex::Regex;
 use std::fs;
 
-pub fn run(files: &[String]) -> Result<()> {
+pub fn run(files: &[String], config: &NoConsoleConfig) -> Result<()> {
     print_header("🔍 Console Statement Check");
 
     let files = filter_files_by_extension(files, &[".ts", ".tsx", ".js", ".jsx"]);
@@ -14,36 +17,48 @@ pub fn run(files: &[String]) -> Result<()> {
     }
 
     let console_pattern = Regex::new(r"console\.(log|debug|info|warn|error)").unwrap();
-    let mut has_console = false;
+    let skip = build_skip_matcher(&config.skip_globs);
 
-    for file in &files {
-        // Skip test files
-        if is_test_file(file) {
-            continue;
-        }
+    // Skip test files, Supabase Edge Functions (Deno-based), and anything
+    // matched by .lefthook-rs.toml's [no_console] skip_globs before handing
+    // the rest to the worker pool - no point reading a file we'd discard.
+    let eligible: Vec<String> = files
+        .iter()
+        .filter(|file| {
+            !is_test_file(file)
+                && !file.starts_with("supabase/functions/")
+                && !skip.as_ref().is_some_and(|m| m.is_match(file.as_str()))
+        })
+        .cloned()
+        .collect();
+
+    let results = utils::walk(&eligible, |file| -> Vec<(usize, String)> {
+        let Ok(content) = fs::read_to_string(file) else { return Vec::new() };
+        content
+            .lines()
+            .enumerate()
+            .filter(|(_, line)| console_pattern.is_match(line))
+            .map(|(i, line)| (i, line.to_string()))
+            .collect()
+    });
 
-        // Skip Supabase Edge Functions (Deno-based)
-        if file.starts_with("supabase/functions/") {
+    let mut has_console = false;
+    for (file, matches) in eligible.iter().zip(results) {
+        let matches = matches.unwrap_or_default();
+        if matches.is_empty() {
             continue;
         }
 
-        if let Ok(content) = fs::read_to_string(file) {
-            let matches: Vec<(usize, &str)> = content
-                .lines()
-                .enumerate()
-                .filter(|(_, line)| console_pattern.is_match(line))
-                .collect();
-
-            if !matches.is_empty() {
-                print_warning(&format!("{} contains console statements:", file));
-                for (line_num, line) in matches.iter().take(5) {
-                    println!("    {}:{}", line_num + 1, line.trim());
-                }
-                if matches.len() > 5 {
-                    println!("    ... and {} more", matches.len() - 5);
-                }
-                has_console = true;
-            }
+        print_warning(&format!("{} contains console statements:", file));
+        for (line_num, line) in matches.iter().take(5) {
+            println!("    {}:{}", line_num + 1, line.trim());
+        }
+        if matches.len() > 5 {
+            println!("    ... and {} more", matches.len() - 5);
+        }
+        has_console = true;
+        for (line_num, _) in &matches {
+            emit(file, line_num + 1, "console-statement", "Console statement found");
         }
     }
 
@@ -58,3 +73,30 @@ pub fn run(files: &[String]) -> Result<()> {
     // Warning only, not blocking
     Ok(())
 }
+
+/// Builds the glob set for `.lefthook-rs.toml`'s `[no_console] skip_globs`,
+/// or `None` when it's empty so the common case skips matching entirely.
+fn build_skip_matcher(globs: &[String]) -> Option<GlobSet> {
+    if globs.is_empty() {
+        return None;
+    }
+    let mut builder = GlobSetBuilder::new();
+    for pattern in globs {
+        if let Ok(glob) = Glob::new(pattern) {
+            builder.add(glob);
+        }
+    }
+    builder.build().ok()
+}
+
+fn emit(file: &str, line: usize, rule: &str, message: &str) {
+    report::push(Finding {
+        check: "no_console".to_string(),
+        file: file.to_string(),
+        line: Some(line as u32),
+        severity: Severity::Warning,
+        rule: rule.to_string(),
+        message: message.to_string(),
+        owasp: None,
+    });
+}
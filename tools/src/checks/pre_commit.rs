@@ -1,7 +1,12 @@
 use crate::checks::{complexity, import_check, nextjs_security, no_console, security};
+use crate::repo_config;
 use crate::utils::{filter_files_by_extension, get_staged_files, print_header, print_error, print_success};
 use anyhow::Result;
 
+/// Runs every sub-check in sequence. Each sub-check already pushes its
+/// structured issues into the shared `report` collector as it prints them,
+/// so `main` can render one combined `--format json`/`--format sarif`
+/// report for the whole pre-commit run instead of one per sub-check.
 pub fn run(files: &[String]) -> Result<()> {
     print_header("🚀 Pre-Commit Checks");
 
@@ -17,6 +22,7 @@ pub fn run(files: &[String]) -> Result<()> {
     }
 
     let ts_files = filter_files_by_extension(&files, &[".ts", ".tsx", ".js", ".jsx"]);
+    let repo_config = repo_config::load();
 
     let mut failed = false;
 
@@ -38,11 +44,11 @@ pub fn run(files: &[String]) -> Result<()> {
 
     // Run no-console check (warning only)
     println!();
-    let _ = no_console::run(&ts_files);
+    let _ = no_console::run(&ts_files, &repo_config.no_console);
 
     // Run import check (warning only)
     println!();
-    let _ = import_check::run(&ts_files);
+    let _ = import_check::run(&ts_files, false);
 
     // Summary
     println!();
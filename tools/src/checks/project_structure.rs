@@ -1,9 +1,11 @@
+use crate::config::{FoodcheckConfig, GlobMatcher};
 use crate::utils::{print_error, print_header, print_info, print_success, print_warning};
 use anyhow::Result;
 use std::fs;
 use std::path::Path;
 
 pub fn run() -> Result<()> {
+    let config = FoodcheckConfig::load(Path::new("."))?;
     print_header("🔍 Project Structure Verification");
 
     let mut passed = 0;
@@ -31,7 +33,7 @@ pub fn run() -> Result<()> {
     // Check docs directory exists
     println!("\n  Checking docs directory...");
     if Path::new("docs").is_dir() {
-        let docs_count = count_files_with_ext("docs", ".md", 10);
+        let docs_count = count_files_matching(&config, "project_structure", "docs", ".md", 10);
         print_success(&format!(
             "docs/ directory exists with {} markdown files",
             docs_count
@@ -139,21 +141,60 @@ pub fn run() -> Result<()> {
     }
 }
 
+/// Counts files under `dir` matching `ext`, honoring the check's configured
+/// `include`/`ignore` globs when `.foodcheck.toml` defines any for `section`,
+/// falling back to the plain extension walk otherwise.
+fn count_files_matching(
+    config: &FoodcheckConfig,
+    section: &str,
+    dir: &str,
+    ext: &str,
+    max_depth: usize,
+) -> usize {
+    let mut globs = config.globs_for(section);
+    if globs.include.is_empty() {
+        if globs.ignore.is_empty() {
+            return count_files_with_ext(dir, ext, max_depth);
+        }
+        // Ignore globs alone still apply, over `dir`'s `ext` files.
+        globs.include.push(format!("{}/**/*{}", dir, ext));
+    }
+
+    match GlobMatcher::build(Path::new("."), &globs) {
+        Ok(matcher) => matcher.collect_files().len(),
+        Err(_) => count_files_with_ext(dir, ext, max_depth),
+    }
+}
+
+/// Counts files by extension, honoring `.gitignore` so build artifacts and
+/// scratch files aren't mistaken for tracked project structure. Pass
+/// `--no-ignore` to fall back to a raw walk when auditing ignored paths.
 fn count_files_with_ext(dir: &str, ext: &str, max_depth: usize) -> usize {
-    walkdir::WalkDir::new(dir)
-        .max_depth(max_depth)
-        .into_iter()
+    if crate::utils::no_ignore() {
+        return walkdir::WalkDir::new(dir)
+            .max_depth(max_depth)
+            .into_iter()
+            .filter_map(|e| e.ok())
+            .filter(|e| e.file_type().is_file())
+            .filter(|e| has_ext(e.path(), ext))
+            .count();
+    }
+
+    ignore::WalkBuilder::new(dir)
+        .max_depth(Some(max_depth))
+        .build()
         .filter_map(|e| e.ok())
-        .filter(|e| e.file_type().is_file())
-        .filter(|e| {
-            e.path()
-                .extension()
-                .map(|e| format!(".{}", e.to_string_lossy()) == ext)
-                .unwrap_or(false)
-        })
+        .filter(|e| e.file_type().map(|t| t.is_file()).unwrap_or(false))
+        .filter(|e| has_ext(e.path(), ext))
         .count()
 }
 
+fn has_ext(path: &Path, ext: &str) -> bool {
+    path.extension()
+        .map(|e| format!(".{}", e.to_string_lossy()) == ext)
+        .unwrap_or(false)
+}
+
 fn count_files_with_ext_depth1(dir: &str, ext: &str) -> usize {
     fs::read_dir(dir)
         .map(|entries| {
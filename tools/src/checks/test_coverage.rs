@@ -1,17 +1,30 @@
+use crate::coverage::{self, Reporter, ScriptCoverage};
 use crate::utils::{print_error, print_header, print_info, print_success, print_warning};
-use anyhow::Result;
-use serde::Deserialize;
+use anyhow::{bail, Context, Result};
+use globset::{Glob, GlobSetBuilder};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
 use std::fs;
+use std::path::Path;
 use std::process::Command;
 
 const MIN_COVERAGE: f64 = 70.0;
 const TARGET_COVERAGE: f64 = 80.0;
+const BASELINE_FILE: &str = "coverage/coverage-baseline.json";
 
 #[derive(Deserialize, Default)]
 struct CoverageSummary {
     total: Option<CoverageTotal>,
 }
 
+/// The full `coverage-summary.json`, including its per-file entries — the
+/// "total" key aside, every other top-level key is a file path.
+#[derive(Deserialize, Default)]
+struct FullCoverageSummary {
+    #[serde(flatten)]
+    entries: HashMap<String, CoverageTotal>,
+}
+
 #[derive(Deserialize, Default)]
 struct CoverageTotal {
     lines: Option<CoverageMetric>,
@@ -82,3 +95,217 @@ pub fn run() -> Result<()> {
 
     Ok(())
 }
+
+/// Same gate as `run`, but sourced from native V8 coverage collected via the
+/// CDP `Profiler` domain (`coverage::collect`) instead of a test-runner's
+/// `coverage-summary.json` — usable with any `test_cmd`, not just ones that
+/// happen to produce that file. `reporter` additionally writes
+/// `coverage/lcov.info` or an HTML tree alongside the console summary.
+pub fn run_native(test_cmd: &[&str], reporter: Reporter) -> Result<()> {
+    print_header("📊 Test Coverage Check (native V8 coverage)");
+
+    let scripts = coverage::collect(test_cmd)?;
+    let scripts = coverage::merge(scripts);
+
+    match reporter {
+        Reporter::Text => {}
+        Reporter::Lcov => {
+            coverage::write_lcov(&scripts, Path::new("coverage/lcov.info"))?;
+            print_info("Wrote coverage/lcov.info");
+        }
+        Reporter::Html => {
+            coverage::write_html(&scripts, Path::new("coverage/html"))?;
+            print_info("Wrote coverage/html/index.html");
+        }
+    }
+
+    let lines = line_coverage_pct(&scripts);
+
+    println!("Coverage Results:");
+    println!("  Lines: {:.1}%", lines);
+    println!();
+
+    if lines < MIN_COVERAGE {
+        print_error(&format!(
+            "Line coverage ({:.1}%) is below minimum threshold ({:.1}%)",
+            lines, MIN_COVERAGE
+        ));
+        return Err(anyhow::anyhow!("Coverage below minimum"));
+    }
+
+    if lines < TARGET_COVERAGE {
+        print_warning(&format!(
+            "Coverage is above minimum but below target ({:.1}%)",
+            TARGET_COVERAGE
+        ));
+    } else {
+        print_success("Coverage meets target threshold!");
+    }
+
+    Ok(())
+}
+
+/// Approximates aggregate line coverage from V8 ranges: a line counts as
+/// covered if any byte offset it spans falls inside a range with `count >
+/// 0`. Ranges with `count == 0` are skipped rather than subtracted, so a
+/// count-0 range nested inside a covered one doesn't mark the lines it
+/// spans as uncovered.
+fn line_coverage_pct(scripts: &[ScriptCoverage]) -> f64 {
+    let mut total_lines = 0usize;
+    let mut covered_lines = 0usize;
+
+    for script in scripts {
+        if script.source.is_empty() {
+            continue;
+        }
+        let line_starts = line_start_offsets(&script.source);
+        let mut covered = vec![false; line_starts.len()];
+
+        for function in &script.functions {
+            for range in &function.ranges {
+                if range.count == 0 {
+                    continue;
+                }
+                for (line_idx, &start) in line_starts.iter().enumerate() {
+                    let end = line_starts
+                        .get(line_idx + 1)
+                        .copied()
+                        .unwrap_or(script.source.len() as u32);
+                    if start < range.end_offset && end > range.start_offset {
+                        covered[line_idx] = true;
+                    }
+                }
+            }
+        }
+
+        total_lines += covered.len();
+        covered_lines += covered.iter().filter(|c| **c).count();
+    }
+
+    if total_lines == 0 {
+        return 0.0;
+    }
+    (covered_lines as f64 / total_lines as f64) * 100.0
+}
+
+#[derive(Serialize, Deserialize, Default)]
+struct Baseline {
+    /// file path -> line coverage pct as of the last `--write-baseline` run.
+    files: HashMap<String, f64>,
+}
+
+fn load_baseline() -> Baseline {
+    fs::read_to_string(BASELINE_FILE)
+        .ok()
+        .and_then(|c| serde_json::from_str(&c).ok())
+        .unwrap_or_default()
+}
+
+fn build_glob_set(patterns: &[String]) -> Result<globset::GlobSet> {
+    let mut builder = GlobSetBuilder::new();
+    for pattern in patterns {
+        builder.add(Glob::new(pattern).with_context(|| format!("invalid glob pattern '{}'", pattern))?);
+    }
+    builder.build().context("building glob set")
+}
+
+/// Per-file coverage gating: unlike `run`'s single aggregate check, this
+/// fails as soon as *any* matched file drops below its floor, so one
+/// badly-covered new file can't hide behind a healthy repo average.
+///
+/// `include`/`exclude` scope which files from `coverage-summary.json` are
+/// considered (matching the Deno coverage tool's own include/exclude glob
+/// flags); when `write_baseline` is set, the current per-file percentages
+/// are recorded to `coverage/coverage-baseline.json` instead of being
+/// checked — later runs then fail only on *regression* against that
+/// baseline rather than requiring every file to already hit `MIN_COVERAGE`.
+pub fn run_per_file(include: &[String], exclude: &[String], write_baseline: bool) -> Result<()> {
+    print_header("📊 Per-File Test Coverage Check");
+
+    let coverage_file = "coverage/coverage-summary.json";
+    if !Path::new(coverage_file).exists() {
+        print_warning("Coverage report not found");
+        print_info("Run 'npm run test:coverage' to generate coverage report");
+        return Ok(());
+    }
+
+    let content = fs::read_to_string(coverage_file)?;
+    let summary: FullCoverageSummary = serde_json::from_str(&content).unwrap_or_default();
+
+    let include_set = build_glob_set(include)?;
+    let exclude_set = build_glob_set(exclude)?;
+
+    let mut files: Vec<(String, f64)> = summary
+        .entries
+        .into_iter()
+        .filter(|(path, _)| path != "total")
+        .filter(|(path, _)| include.is_empty() || include_set.is_match(path))
+        .filter(|(path, _)| exclude.is_empty() || !exclude_set.is_match(path))
+        .map(|(path, total)| {
+            let pct = total.lines.and_then(|m| m.pct).unwrap_or(0.0);
+            (path, pct)
+        })
+        .collect();
+    files.sort_by(|a, b| a.0.cmp(&b.0));
+
+    if files.is_empty() {
+        bail!("include/exclude filters matched zero files in {} — refusing to report an empty pass", coverage_file);
+    }
+
+    if write_baseline {
+        let baseline = Baseline {
+            files: files.iter().cloned().collect(),
+        };
+        if let Some(parent) = Path::new(BASELINE_FILE).parent() {
+            fs::create_dir_all(parent)?;
+        }
+        fs::write(BASELINE_FILE, serde_json::to_string_pretty(&baseline)?)?;
+        print_success(&format!("Wrote baseline for {} file(s) to {}", files.len(), BASELINE_FILE));
+        return Ok(());
+    }
+
+    let baseline = load_baseline();
+    let mut failed = 0;
+
+    for (path, pct) in &files {
+        if let Some(&baseline_pct) = baseline.files.get(path) {
+            if *pct < baseline_pct {
+                print_error(&format!(
+                    "{}: coverage regressed ({:.1}% -> {:.1}%)",
+                    path, baseline_pct, pct
+                ));
+                failed += 1;
+                continue;
+            }
+        } else if *pct < MIN_COVERAGE {
+            print_error(&format!(
+                "{}: {:.1}% is below the {:.1}% floor",
+                path, pct, MIN_COVERAGE
+            ));
+            failed += 1;
+            continue;
+        }
+        print_success(&format!("{}: {:.1}%", path, pct));
+    }
+
+    if failed > 0 {
+        println!();
+        print_error(&format!("{} file(s) failed the per-file coverage gate", failed));
+        Err(anyhow::anyhow!("Per-file coverage check failed"))
+    } else {
+        print_success("All matched files meet their coverage floor");
+        Ok(())
+    }
+}
+
+fn line_start_offsets(source: &str) -> Vec<u32> {
+    let mut offsets = vec![0u32];
+    let mut offset = 0u32;
+    for ch in source.bytes() {
+        offset += 1;
+        if ch == b'\n' {
+            offsets.push(offset);
+        }
+    }
+    offsets
+}
@@ -1,5 +1,38 @@
 mod checks;
+// These modules live under scripts/lefthook/src/ rather than tools/src/ -
+// both directories make up the same lefthook-rs binary crate (see
+// tools/src/checks/mod.rs for the same split on the checks:: submodules),
+// there's just no single Cargo.toml yet to merge them into one src/ tree.
+#[path = "../../scripts/lefthook/src/access_control.rs"]
+mod access_control;
+#[path = "../../scripts/lefthook/src/ast_query.rs"]
+mod ast_query;
+#[path = "../../scripts/lefthook/src/config.rs"]
+mod config;
+#[path = "../../scripts/lefthook/src/coverage/mod.rs"]
+mod coverage;
+#[path = "../../scripts/lefthook/src/jwt_inspect.rs"]
+mod jwt_inspect;
+#[path = "../../scripts/lefthook/src/osv_advisories.rs"]
+mod osv_advisories;
+#[path = "../../scripts/lefthook/src/report.rs"]
+mod report;
+#[path = "../../scripts/lefthook/src/repo_config.rs"]
+mod repo_config;
+#[path = "../../scripts/lefthook/src/rule_pack.rs"]
+mod rule_pack;
+#[path = "../../scripts/lefthook/src/secrets_ignore.rs"]
+mod secrets_ignore;
+#[path = "../../scripts/lefthook/src/security_baseline.rs"]
+mod security_baseline;
+#[path = "../../scripts/lefthook/src/source_map.rs"]
+mod source_map;
+#[path = "../../scripts/lefthook/src/sri.rs"]
+mod sri;
+#[path = "../../scripts/lefthook/src/utils.rs"]
 mod utils;
+#[path = "../../scripts/lefthook/src/watch.rs"]
+mod watch;
 
 use anyhow::Result;
 use clap::{Parser, Subcommand};
@@ -14,6 +47,17 @@ struct Cli {
     /// Enable verbose output
     #[arg(short, long, global = true)]
     verbose: bool,
+
+    /// Also scan paths that .gitignore/.git/info/exclude would normally hide
+    #[arg(long, global = true)]
+    no_ignore: bool,
+
+    /// Output format for machine-readable findings, in addition to the
+    /// normal colored text (json, sarif, and github print to stdout after
+    /// the run; github emits `::error`/`::warning` workflow commands for PR
+    /// annotations)
+    #[arg(long, global = true, default_value = "text")]
+    format: String,
 }
 
 #[derive(Subcommand)]
@@ -23,6 +67,9 @@ enum Commands {
         /// Files to check (reads from stdin if not provided)
         #[arg(trailing_var_arg = true)]
         files: Vec<String>,
+        /// Stay resident and re-run on file changes
+        #[arg(long)]
+        watch: bool,
     },
     /// Validate conventional commit message format
     ConventionalCommit {
@@ -43,18 +90,30 @@ enum Commands {
         /// Files to check
         #[arg(trailing_var_arg = true)]
         files: Vec<String>,
+        /// Stay resident and re-run on file changes
+        #[arg(long)]
+        watch: bool,
     },
     /// Check for console statements
     NoConsole {
         /// Files to check
         #[arg(trailing_var_arg = true)]
         files: Vec<String>,
+        /// Stay resident and re-run on file changes
+        #[arg(long)]
+        watch: bool,
     },
     /// Check import organization
     ImportCheck {
         /// Files to check
         #[arg(trailing_var_arg = true)]
         files: Vec<String>,
+        /// Stay resident and re-run on file changes
+        #[arg(long)]
+        watch: bool,
+        /// Rewrite out-of-order import blocks into grouped/sorted form and re-stage them
+        #[arg(long)]
+        fix: bool,
     },
     /// Run dependency vulnerability audit
     DependencyAudit,
@@ -63,49 +122,177 @@ enum Commands {
         /// Files to check
         #[arg(trailing_var_arg = true)]
         files: Vec<String>,
+        /// Stay resident and re-run on file changes
+        #[arg(long)]
+        watch: bool,
     },
     /// Analyze bundle size
     BundleSize,
     /// Check test coverage
-    TestCoverage,
+    TestCoverage {
+        /// Also write a report in this format alongside the console summary
+        #[arg(long, default_value = "text")]
+        reporter: String,
+        /// Only gate these glob-matched files individually (repeatable)
+        #[arg(long = "include")]
+        include: Vec<String>,
+        /// Exclude these glob-matched files from the per-file gate (repeatable)
+        #[arg(long = "exclude")]
+        exclude: Vec<String>,
+        /// Record current per-file coverage as the regression baseline instead of checking it
+        #[arg(long)]
+        write_baseline: bool,
+    },
     /// Check for unused exports / dead code
-    UnusedExports,
+    UnusedExports {
+        /// Also cross-reference against V8 coverage collected by running a test command (e.g. `--with-coverage -- npm test`)
+        #[arg(long)]
+        with_coverage: bool,
+        /// Test command to run under the inspector when --with-coverage is set
+        #[arg(trailing_var_arg = true)]
+        test_cmd: Vec<String>,
+    },
     /// Next.js/React/Vercel security vulnerabilities check
     NextjsSecurity {
         /// Files to check
         #[arg(trailing_var_arg = true)]
         files: Vec<String>,
+        /// Stay resident and re-run on file changes
+        #[arg(long)]
+        watch: bool,
+        /// Maximum allowed Critical-severity findings before the run fails (CI gate)
+        #[arg(long, default_value = "0")]
+        max_critical: usize,
+        /// Maximum allowed High-severity findings before the run fails (CI gate)
+        #[arg(long, default_value = "0")]
+        max_high: usize,
+        /// Maximum allowed Medium-severity findings before the run fails (unset: advisory only)
+        #[arg(long)]
+        max_medium: Option<usize>,
+        /// Maximum allowed Low-severity findings before the run fails (unset: advisory only)
+        #[arg(long)]
+        max_low: Option<usize>,
+        /// Also live-scan this deployed URL's TLS posture (testssl.sh) and response headers
+        #[arg(long)]
+        scan_url: Option<String>,
+        /// Fetch external <script>/<link> URLs to compute/verify SRI hashes (opt-in: network access)
+        #[arg(long)]
+        verify_sri: bool,
+        /// Record current findings as the suppression baseline instead of gating on them
+        #[arg(long)]
+        write_baseline: bool,
     },
     /// Run all pre-commit checks
     PreCommit {
         /// Files to check
         #[arg(trailing_var_arg = true)]
         files: Vec<String>,
+        /// Stay resident and re-run affected checks on file changes
+        #[arg(long)]
+        watch: bool,
+    },
+    /// Continuously re-run a check whenever a tracked source file changes,
+    /// without passing --watch to that command every time
+    Watch {
+        /// Which check to re-run: "security" or "nextjs-security"
+        check: String,
+        /// Files to check (recomputed from staged files each cycle if empty)
+        #[arg(trailing_var_arg = true)]
+        files: Vec<String>,
     },
 }
 
 fn main() -> Result<()> {
     let cli = Cli::parse();
     utils::set_verbose(cli.verbose);
+    utils::set_no_ignore(cli.no_ignore);
+    let format = report::Format::parse(&cli.format)
+        .ok_or_else(|| anyhow::anyhow!("unknown --format '{}' (expected text, json, sarif, or github)", cli.format))?;
+    let repo_config = repo_config::load();
 
     let result = match cli.command {
-        Commands::Security { files } => checks::security::run(&files),
+        Commands::Security { files, watch } => {
+            watch::run(watch, &files, |f| checks::security::run(f), watch::staged_files)
+        }
         Commands::ConventionalCommit { message_file } => {
-            checks::conventional_commit::run(&message_file)
+            checks::conventional_commit::run(&message_file, &repo_config.conventional_commit)
         }
         Commands::ProtectedBranch => checks::protected_branch::run(),
-        Commands::LargeFiles { max_size } => checks::large_files::run(max_size),
-        Commands::Complexity { files } => checks::complexity::run(&files),
-        Commands::NoConsole { files } => checks::no_console::run(&files),
-        Commands::ImportCheck { files } => checks::import_check::run(&files),
+        Commands::LargeFiles { max_size } => {
+            checks::large_files::run(repo_config.large_files.max_size_kb.unwrap_or(max_size))
+        }
+        Commands::Complexity { files, watch } => {
+            watch::run(watch, &files, |f| checks::complexity::run(f), watch::staged_files)
+        }
+        Commands::NoConsole { files, watch } => {
+            watch::run(watch, &files, |f| checks::no_console::run(f, &repo_config.no_console), watch::staged_files)
+        }
+        Commands::ImportCheck { files, watch, fix } => {
+            watch::run(watch, &files, |f| checks::import_check::run(f, fix), watch::staged_files)
+        }
         Commands::DependencyAudit => checks::dependency_audit::run(),
-        Commands::Accessibility { files } => checks::accessibility::run(&files),
+        Commands::Accessibility { files, watch } => {
+            watch::run(watch, &files, |f| checks::accessibility::run(f), watch::staged_files)
+        }
         Commands::BundleSize => checks::bundle_size::run(),
-        Commands::TestCoverage => checks::test_coverage::run(),
-        Commands::UnusedExports => checks::unused_exports::run(),
-        Commands::NextjsSecurity { files } => checks::nextjs_security::run(&files),
-        Commands::PreCommit { files } => checks::pre_commit::run(&files),
+        Commands::TestCoverage { reporter, include, exclude, write_baseline } => {
+            if !include.is_empty() || !exclude.is_empty() || write_baseline {
+                checks::test_coverage::run_per_file(&include, &exclude, write_baseline)
+            } else {
+                let reporter = coverage::Reporter::parse(&reporter)
+                    .ok_or_else(|| anyhow::anyhow!("unknown --reporter '{}' (expected text, lcov, or html)", reporter))?;
+                match reporter {
+                    coverage::Reporter::Text => checks::test_coverage::run(),
+                    _ => checks::test_coverage::run_native(&["npm", "run", "test:coverage"], reporter),
+                }
+            }
+        }
+        Commands::UnusedExports { with_coverage, test_cmd } => {
+            if with_coverage {
+                if test_cmd.is_empty() {
+                    Err(anyhow::anyhow!(
+                        "--with-coverage requires a test command, e.g. `lefthook-rs unused-exports --with-coverage -- npm test`"
+                    ))
+                } else {
+                    let test_cmd: Vec<&str> = test_cmd.iter().map(String::as_str).collect();
+                    checks::unused_exports::run_with_coverage(&test_cmd)
+                }
+            } else {
+                checks::unused_exports::run()
+            }
+        }
+        Commands::NextjsSecurity { files, watch, max_critical, max_high, max_medium, max_low, scan_url, verify_sri, write_baseline } => {
+            let thresholds = checks::nextjs_security::GateThresholds {
+                max_critical: Some(max_critical),
+                max_high: Some(max_high),
+                max_medium,
+                max_low,
+            };
+            watch::run(
+                watch,
+                &files,
+                |f| checks::nextjs_security::run_full(f, thresholds, scan_url.as_deref(), verify_sri, write_baseline),
+                watch::staged_files,
+            )
+        }
+        Commands::PreCommit { files, watch } => {
+            watch::run(watch, &files, |f| checks::pre_commit::run(f), watch::staged_files)
+        }
+        Commands::Watch { check, files } => match check.as_str() {
+            "security" => watch::run(true, &files, |f| checks::security::run(f), watch::staged_files),
+            "nextjs-security" => watch::run(true, &files, |f| checks::nextjs_security::run(f), watch::staged_files),
+            other => Err(anyhow::anyhow!(
+                "unknown check '{}' (expected security or nextjs-security)",
+                other
+            )),
+        },
     };
 
+    let findings = report::take_all();
+    let rendered = report::render(&findings, format);
+    if !rendered.is_empty() {
+        println!("{}", rendered);
+    }
+
     std::process::exit(if result.is_ok() { 0 } else { 1 });
 }